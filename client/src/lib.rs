@@ -0,0 +1,186 @@
+use anyhow::{Context, Error};
+use input::EventSink;
+use net::record::{Format, Record, Recorder};
+use net::{self, Message, PROTOCOL_VERSION};
+use std::convert::Infallible;
+use std::path::Path;
+use tokio::fs::{self, File};
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time;
+use tokio_native_tls::native_tls::{Certificate, TlsConnector};
+
+// Connects to the server and writes the events it sends to the sink.
+pub async fn run<S: EventSink>(
+    server: &str,
+    port: u16,
+    certificate_path: &Path,
+    mut sink: S,
+    record_path: Option<&Path>,
+) -> Result<Infallible, Error> {
+    let certificate = fs::read(certificate_path)
+        .await
+        .context("Failed to read certificate")?;
+    let certificate = Certificate::from_der(&certificate)
+        .or_else(|_| Certificate::from_pem(&certificate))
+        .context("Failed to parse certificate")?;
+
+    let connector: tokio_native_tls::TlsConnector = TlsConnector::builder()
+        .add_root_certificate(certificate)
+        .build()
+        .context("Failed to create connector")?
+        .into();
+
+    let stream = TcpStream::connect((server, port)).await?;
+    let stream = BufReader::new(stream);
+    let mut stream = connector
+        .connect(server, stream)
+        .await
+        .context("Failed to connect")?;
+
+    log::info!("Connected to {}:{}", server, port);
+
+    net::write_version(&mut stream, PROTOCOL_VERSION).await?;
+
+    let version = net::read_version(&mut stream).await?;
+    if version != PROTOCOL_VERSION {
+        return Err(anyhow::anyhow!(
+            "Incompatible protocol version (got {}, expecting {})",
+            version,
+            PROTOCOL_VERSION
+        ));
+    }
+
+    let (mut read_stream, mut write_stream) = tokio::io::split(stream);
+    let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let message = time::timeout(net::MESSAGE_TIMEOUT, net::read_message(&mut read_stream))
+                .await
+                .context("Read timed out")
+                .and_then(|message| message.map_err(Into::into));
+            let failed = message.is_err();
+            if message_sender.send(message).is_err() || failed {
+                return;
+            }
+        }
+    });
+
+    let mut recorder = match record_path {
+        Some(path) => {
+            let file = File::create(path)
+                .await
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            Some(Recorder::new(file, Format::from_path(path)).await?)
+        }
+        None => None,
+    };
+
+    loop {
+        tokio::select! {
+            message = message_receiver.recv() => {
+                // The reading task only exits after sending an error.
+                let message = message.unwrap()?;
+                if let (Some(record), Some(recorder)) = (Record::from_message(&message), &mut recorder) {
+                    recorder.write(record).await?;
+                }
+
+                handle_message(&mut sink, message).await?;
+            }
+            led = sink.read_led() => {
+                let (device, led, on) = led?;
+                time::timeout(
+                    net::MESSAGE_TIMEOUT,
+                    net::write_message(&mut write_stream, &Message::Led { device, led, on }),
+                )
+                .await
+                .context("Write timeout")??;
+            }
+        }
+    }
+}
+
+async fn handle_message<S: EventSink>(sink: &mut S, message: Message) -> Result<(), Error> {
+    match message {
+        Message::Event {
+            device: Some(id),
+            event,
+        } => sink.write_device(id, event).await?,
+        Message::Event {
+            device: None,
+            event,
+        } => sink.write(event).await?,
+        Message::KeepAlive => {}
+        Message::DeviceAdded { id, info } => sink.add_device(id, info).await?,
+        Message::DeviceRemoved { id } => sink.remove_device(id).await?,
+        // Only ever sent by clients.
+        Message::Led { .. } => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::mock::{self, Output};
+    use input::{DeviceInfo, Direction, Event, Key, KeyKind};
+
+    fn key(key: Key, direction: Direction) -> Event {
+        Event::Key {
+            direction,
+            kind: KeyKind::Key(key),
+        }
+    }
+
+    #[tokio::test]
+    async fn messages_are_written_to_mirrors() {
+        let (mut sink, mut handle) = mock::sink();
+        let id = mock::device_id();
+        let info = DeviceInfo {
+            name: "Keyboard".to_owned(),
+            keys: vec![KeyKind::Key(Key::A)],
+            ..Default::default()
+        };
+
+        let messages = vec![
+            Message::DeviceAdded {
+                id,
+                info: info.clone(),
+            },
+            Message::KeepAlive,
+            Message::Event {
+                device: Some(id),
+                event: key(Key::A, Direction::Down),
+            },
+            Message::Event {
+                device: None,
+                event: key(Key::B, Direction::Down),
+            },
+            Message::DeviceRemoved { id },
+        ];
+
+        for message in messages {
+            handle_message(&mut sink, message).await.unwrap();
+        }
+
+        assert_eq!(handle.try_next(), Some(Output::DeviceAdded { id, info }));
+        assert_eq!(
+            handle.try_next(),
+            Some(Output::Event {
+                device: Some(id),
+                event: key(Key::A, Direction::Down),
+            })
+        );
+        assert_eq!(
+            handle.try_next(),
+            Some(Output::Event {
+                device: None,
+                event: key(Key::B, Direction::Down),
+            })
+        );
+        assert_eq!(handle.try_next(), Some(Output::DeviceRemoved { id }));
+        assert_eq!(handle.try_next(), None);
+    }
+}
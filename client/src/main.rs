@@ -1,64 +1,25 @@
 mod config;
 
-use anyhow::{Context, Error};
+use anyhow::Error;
 use config::Config;
 use input::EventWriter;
 use log::LevelFilter;
-use net::{self, Message, PROTOCOL_VERSION};
 use std::convert::Infallible;
 use std::path::{Path, PathBuf};
 use std::process;
 use structopt::StructOpt;
 use tokio::fs;
-use tokio::io::BufReader;
-use tokio::net::TcpStream;
-use tokio::time;
-use tokio_native_tls::native_tls::{Certificate, TlsConnector};
 
-async fn run(server: &str, port: u16, certificate_path: &Path) -> Result<Infallible, Error> {
-    let certificate = fs::read(certificate_path)
-        .await
-        .context("Failed to read certificate")?;
-    let certificate = Certificate::from_der(&certificate)
-        .or_else(|_| Certificate::from_pem(&certificate))
-        .context("Failed to parse certificate")?;
-
-    let connector: tokio_native_tls::TlsConnector = TlsConnector::builder()
-        .add_root_certificate(certificate)
-        .build()
-        .context("Failed to create connector")?
-        .into();
-
-    let stream = TcpStream::connect((server, port)).await?;
-    let stream = BufReader::new(stream);
-    let mut stream = connector
-        .connect(server, stream)
-        .await
-        .context("Failed to connect")?;
-
-    log::info!("Connected to {}:{}", server, port);
-
-    net::write_version(&mut stream, PROTOCOL_VERSION).await?;
-
-    let version = net::read_version(&mut stream).await?;
-    if version != PROTOCOL_VERSION {
-        return Err(anyhow::anyhow!(
-            "Incompatible protocol version (got {}, expecting {})",
-            version,
-            PROTOCOL_VERSION
-        ));
-    }
-
-    let mut writer = EventWriter::new().await?;
-    loop {
-        let message = time::timeout(net::MESSAGE_TIMEOUT, net::read_message(&mut stream))
-            .await
-            .context("Read timed out")??;
-        match message {
-            Message::Event(event) => writer.write(event).await?,
-            Message::KeepAlive => {}
-        }
-    }
+async fn run(config: &Config, record_path: Option<&Path>) -> Result<Infallible, Error> {
+    let writer = EventWriter::new().await?;
+    client::run(
+        &config.server.hostname,
+        config.server.port,
+        &config.certificate_path,
+        writer,
+        record_path,
+    )
+    .await
 }
 
 #[derive(StructOpt)]
@@ -74,6 +35,11 @@ struct Args {
         structopt(default_value = "C:/rkvm/client.toml")
     )]
     config_path: PathBuf,
+    #[structopt(
+        long,
+        help = "Record received events into a file, ending with .jsonl for JSON lines"
+    )]
+    record: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -101,11 +67,10 @@ async fn main() {
     };
 
     tokio::select! {
-        result = run(&config.server.hostname, config.server.port, &config.certificate_path) => {
-            if let Err(err) = result {
-                log::error!("Error: {:#}", err);
-                process::exit(1);
-            }
+        result = run(&config, args.record.as_deref()) => {
+            let Err(err) = result;
+            log::error!("Error: {:#}", err);
+            process::exit(1);
         }
         result = tokio::signal::ctrl_c() => {
             if let Err(err) = result {
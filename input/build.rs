@@ -1,15 +1,37 @@
-use bindgen::{Builder, CargoCallbacks};
-use pkg_config::Config;
+#[path = "build/codes.rs"]
+mod codes;
+
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() {
+    println!("cargo:rerun-if-changed=build/codes.rs");
+    println!("cargo:rerun-if-changed={}", codes::HEADER);
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    // The key enums are needed everywhere, the codes themselves only on Linux.
+    codes::generate(&out_path);
+
     match env::var("CARGO_CFG_TARGET_OS").unwrap().as_str() {
         "windows" => return,
         "linux" => {}
         _ => panic!("Unsupported target OS"),
     }
 
+    // The ioctl backend only needs the codes generated above.
+    if env::var_os("CARGO_FEATURE_IOCTL").is_some() {
+        return;
+    }
+
+    generate_bindings(&out_path);
+}
+
+#[cfg(feature = "libevdev")]
+fn generate_bindings(out_path: &Path) {
+    use bindgen::{Builder, CargoCallbacks};
+    use pkg_config::Config;
+
     println!("cargo:rerun-if-changed=glue/glue.h");
 
     let library = Config::new()
@@ -28,6 +50,10 @@ fn main() {
         .generate()
         .unwrap();
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     bindings.write_to_file(out_path.join("glue.rs")).unwrap();
 }
+
+#[cfg(not(feature = "libevdev"))]
+fn generate_bindings(_: &Path) {
+    panic!("Either the libevdev or the ioctl feature has to be enabled");
+}
@@ -0,0 +1,376 @@
+// Generates the Key and Button enums along with their Linux codes from input-event-codes.h.
+//
+// The header is vendored rather than taken from the system, so that all machines agree on the list of keys
+// regardless of their kernel version, because the enums are sent over the network.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+pub const HEADER: &str = "glue/input-event-codes.h";
+
+// Defines that don't name an actual key.
+const IGNORED: &[&str] = &["KEY_RESERVED", "KEY_MIN_INTERESTING", "KEY_MAX", "KEY_CNT"];
+
+// Defines that share their code with another one. The enum variant is named after the second one,
+// the first one is only accepted as an alternative name.
+const ALIASES: &[(&str, &str)] = &[
+    ("KEY_HANGUEL", "KEY_HANGEUL"),
+    ("KEY_SCREENLOCK", "KEY_COFFEE"),
+    ("KEY_DIRECTION", "KEY_ROTATE_DISPLAY"),
+    ("KEY_DASHBOARD", "KEY_ALL_APPLICATIONS"),
+    ("KEY_BRIGHTNESS_ZERO", "KEY_BRIGHTNESS_AUTO"),
+    ("KEY_WIMAX", "KEY_WWAN"),
+    ("KEY_ZOOM", "KEY_FULL_SCREEN"),
+    ("KEY_SCREEN", "KEY_ASPECT_RATIO"),
+    ("KEY_BRIGHTNESS_TOGGLE", "KEY_DISPLAYTOGGLE"),
+    ("BTN_MISC", "BTN_0"),
+    ("BTN_MOUSE", "BTN_LEFT"),
+    ("BTN_JOYSTICK", "BTN_TRIGGER"),
+    ("BTN_GAMEPAD", "BTN_SOUTH"),
+    ("BTN_A", "BTN_SOUTH"),
+    ("BTN_B", "BTN_EAST"),
+    ("BTN_X", "BTN_NORTH"),
+    ("BTN_Y", "BTN_WEST"),
+    ("BTN_DIGI", "BTN_TOOL_PEN"),
+    ("BTN_WHEEL", "BTN_GEAR_DOWN"),
+    ("BTN_TRIGGER_HAPPY", "BTN_TRIGGER_HAPPY1"),
+];
+
+// Names which can't be derived from the define, because the kernel doesn't separate the words.
+const NAMES: &[(&str, &str)] = &[
+    ("KEY_LEFTBRACE", "LeftBrace"),
+    ("KEY_RIGHTBRACE", "RightBrace"),
+    ("KEY_LEFTCTRL", "LeftCtrl"),
+    ("KEY_LEFTSHIFT", "LeftShift"),
+    ("KEY_RIGHTSHIFT", "RightShift"),
+    ("KEY_KPASTERISK", "KpAsterisk"),
+    ("KEY_LEFTALT", "LeftAlt"),
+    ("KEY_CAPSLOCK", "CapsLock"),
+    ("KEY_NUMLOCK", "NumLock"),
+    ("KEY_SCROLLLOCK", "ScrollLock"),
+    ("KEY_KPMINUS", "KpMinus"),
+    ("KEY_KPPLUS", "KpPlus"),
+    ("KEY_KPDOT", "KpDot"),
+    ("KEY_ZENKAKUHANKAKU", "ZenkakuHankaku"),
+    ("KEY_KATAKANAHIRAGANA", "KatakanaHiragana"),
+    ("KEY_KPJPCOMMA", "KpJpComma"),
+    ("KEY_KPENTER", "KpEnter"),
+    ("KEY_RIGHTCTRL", "RightCtrl"),
+    ("KEY_KPSLASH", "KpSlash"),
+    ("KEY_SYSRQ", "SysRq"),
+    ("KEY_RIGHTALT", "RightAlt"),
+    ("KEY_LINEFEED", "LineFeed"),
+    ("KEY_PAGEUP", "PageUp"),
+    ("KEY_PAGEDOWN", "PageDown"),
+    ("KEY_VOLUMEDOWN", "VolumeDown"),
+    ("KEY_VOLUMEUP", "VolumeUp"),
+    ("KEY_KPEQUAL", "KpEqual"),
+    ("KEY_KPPLUSMINUS", "KpPlusMinus"),
+    ("KEY_KPCOMMA", "KpComma"),
+    ("KEY_LEFTMETA", "LeftMeta"),
+    ("KEY_RIGHTMETA", "RightMeta"),
+    ("KEY_WAKEUP", "WakeUp"),
+    ("KEY_SENDFILE", "SendFile"),
+    ("KEY_DELETEFILE", "DeleteFile"),
+    ("KEY_MSDOS", "MsDos"),
+    ("KEY_CYCLEWINDOWS", "CycleWindows"),
+    ("KEY_CLOSECD", "CloseCd"),
+    ("KEY_EJECTCD", "EjectCd"),
+    ("KEY_EJECTCLOSECD", "EjectCloseCd"),
+    ("KEY_NEXTSONG", "NextSong"),
+    ("KEY_PLAYPAUSE", "PlayPause"),
+    ("KEY_PREVIOUSSONG", "PreviousSong"),
+    ("KEY_STOPCD", "StopCd"),
+    ("KEY_SCROLLUP", "ScrollUp"),
+    ("KEY_SCROLLDOWN", "ScrollDown"),
+    ("KEY_KPLEFTPAREN", "KpLeftParen"),
+    ("KEY_KPRIGHTPAREN", "KpRightParen"),
+    ("KEY_PLAYCD", "PlayCd"),
+    ("KEY_PAUSECD", "PauseCd"),
+    ("KEY_FASTFORWARD", "FastForward"),
+    ("KEY_BASSBOOST", "BassBoost"),
+    ("KEY_ALTERASE", "AltErase"),
+    ("KEY_BRIGHTNESSDOWN", "BrightnessDown"),
+    ("KEY_BRIGHTNESSUP", "BrightnessUp"),
+    ("KEY_SWITCHVIDEOMODE", "SwitchVideoMode"),
+    ("KEY_KBDILLUMTOGGLE", "KbdIllumToggle"),
+    ("KEY_KBDILLUMDOWN", "KbdIllumDown"),
+    ("KEY_KBDILLUMUP", "KbdIllumUp"),
+    ("KEY_FORWARDMAIL", "ForwardMail"),
+    ("KEY_RFKILL", "RfKill"),
+    ("KEY_MICMUTE", "MicMute"),
+    ("KEY_CHANNELUP", "ChannelUp"),
+    ("KEY_CHANNELDOWN", "ChannelDown"),
+    ("KEY_VIDEOPHONE", "VideoPhone"),
+    ("KEY_ZOOMIN", "ZoomIn"),
+    ("KEY_ZOOMOUT", "ZoomOut"),
+    ("KEY_ZOOMRESET", "ZoomReset"),
+    ("KEY_WORDPROCESSOR", "WordProcessor"),
+    ("KEY_GRAPHICSEDITOR", "GraphicsEditor"),
+    ("KEY_VOICEMAIL", "VoiceMail"),
+    ("KEY_ADDRESSBOOK", "AddressBook"),
+    ("KEY_DISPLAYTOGGLE", "DisplayToggle"),
+    ("KEY_LOGOFF", "LogOff"),
+    ("KEY_FRAMEFORWARD", "FrameForward"),
+    ("KEY_10CHANNELSUP", "N10ChannelsUp"),
+    ("KEY_10CHANNELSDOWN", "N10ChannelsDown"),
+    ("KEY_CAMERA_ZOOMIN", "CameraZoomIn"),
+    ("KEY_CAMERA_ZOOMOUT", "CameraZoomOut"),
+    ("KEY_BUTTONCONFIG", "ButtonConfig"),
+    ("KEY_TASKMANAGER", "TaskManager"),
+    ("KEY_VOICECOMMAND", "VoiceCommand"),
+    ("KEY_KBDINPUTASSIST_PREV", "KbdInputAssistPrev"),
+    ("KEY_KBDINPUTASSIST_NEXT", "KbdInputAssistNext"),
+    ("KEY_KBDINPUTASSIST_PREVGROUP", "KbdInputAssistPrevgroup"),
+    ("KEY_KBDINPUTASSIST_NEXTGROUP", "KbdInputAssistNextgroup"),
+    ("KEY_KBDINPUTASSIST_ACCEPT", "KbdInputAssistAccept"),
+    ("KEY_KBDINPUTASSIST_CANCEL", "KbdInputAssistCancel"),
+    ("KEY_FASTREVERSE", "FastReverse"),
+    ("KEY_SLOWREVERSE", "SlowReverse"),
+];
+
+struct Code {
+    name: String,
+    code: u16,
+    aliases: Vec<String>,
+}
+
+pub fn generate(out_path: &Path) {
+    let header = fs::read_to_string(HEADER).unwrap();
+    let mut values = HashMap::new();
+    let mut constants = BTreeMap::new();
+    for line in header.lines() {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("#define") {
+            continue;
+        }
+
+        let (define, value) = match (parts.next(), parts.next()) {
+            (Some(define), Some(value)) => (define, value),
+            _ => continue,
+        };
+
+        if let Some(code) = parse_code(value) {
+            constants.insert(define, code);
+        }
+
+        if (define.starts_with("KEY_") || define.starts_with("BTN_")) && !IGNORED.contains(&define)
+        {
+            values.insert(define, value);
+        }
+    }
+
+    let aliases: HashMap<_, _> = ALIASES.iter().copied().collect();
+    let names: HashMap<_, _> = NAMES.iter().copied().collect();
+
+    let mut keys = BTreeMap::new();
+    let mut buttons = BTreeMap::new();
+    for (define, value) in &values {
+        if aliases.contains_key(define) {
+            continue;
+        }
+
+        let code = parse_code(value)
+            .unwrap_or_else(|| panic!("{} is an alias of {}, add it to ALIASES", define, value));
+
+        let codes = if define.starts_with("KEY_") {
+            &mut keys
+        } else {
+            &mut buttons
+        };
+
+        let code = Code {
+            name: variant_name(define, &names),
+            code,
+            aliases: Vec::new(),
+        };
+
+        if let Some(other) = codes.insert(code.code, code) {
+            panic!(
+                "{} has the same code as {}, add one of them to ALIASES",
+                define, other.name
+            );
+        }
+    }
+
+    for (alias, define) in ALIASES {
+        let value = values
+            .get(alias)
+            .unwrap_or_else(|| panic!("{} is not defined", alias));
+        let code = parse_code(values[define]).unwrap();
+
+        // Make sure the alias really refers to the same code, either directly or by naming the other define.
+        assert!(
+            *value == *define || parse_code(value) == Some(code),
+            "{} is not an alias of {}",
+            alias,
+            define
+        );
+
+        let codes = if define.starts_with("KEY_") {
+            &mut keys
+        } else {
+            &mut buttons
+        };
+
+        codes
+            .get_mut(&code)
+            .unwrap()
+            .aliases
+            .push(variant_name(alias, &names));
+    }
+
+    for codes in &[&keys, &buttons] {
+        let mut seen = HashSet::new();
+        for code in codes.values() {
+            for name in std::iter::once(&code.name).chain(&code.aliases) {
+                assert!(seen.insert(name), "Duplicate name {}", name);
+            }
+        }
+    }
+
+    fs::write(out_path.join("key.rs"), generate_enum("Key", &keys)).unwrap();
+    fs::write(
+        out_path.join("button.rs"),
+        generate_enum("Button", &buttons),
+    )
+    .unwrap();
+    fs::write(out_path.join("key_codes.rs"), generate_codes("Key", &keys)).unwrap();
+    fs::write(
+        out_path.join("button_codes.rs"),
+        generate_codes("Button", &buttons),
+    )
+    .unwrap();
+    fs::write(
+        out_path.join("event_codes.rs"),
+        generate_constants(&constants),
+    )
+    .unwrap();
+}
+
+// All numeric defines of the header as constants, the same as bindgen would generate them.
+fn generate_constants(constants: &BTreeMap<&str, u16>) -> String {
+    let mut output = String::new();
+    for (define, code) in constants {
+        writeln!(output, "pub const {}: u32 = {};", define, code).unwrap();
+    }
+
+    output
+}
+
+fn parse_code(value: &str) -> Option<u16> {
+    if let Some(hex) = value.strip_prefix("0x") {
+        return u16::from_str_radix(hex, 16).ok();
+    }
+
+    value.parse().ok()
+}
+
+fn variant_name(define: &str, names: &HashMap<&str, &str>) -> String {
+    if let Some(name) = names.get(define) {
+        return name.to_string();
+    }
+
+    let mut name = String::new();
+    for word in define.split('_').skip(1) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.push(first);
+            name.extend(chars.map(|c| c.to_ascii_lowercase()));
+        }
+    }
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, 'N');
+    }
+
+    name
+}
+
+fn generate_enum(r#enum: &str, codes: &BTreeMap<u16, Code>) -> String {
+    let mut output = String::new();
+
+    writeln!(
+        output,
+        "#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]"
+    )
+    .unwrap();
+    writeln!(output, "pub enum {} {{", r#enum).unwrap();
+    for code in codes.values() {
+        for alias in &code.aliases {
+            writeln!(output, "    #[serde(alias = \"{}\")]", alias).unwrap();
+        }
+
+        writeln!(output, "    {},", code.name).unwrap();
+    }
+    writeln!(output, "}}").unwrap();
+
+    writeln!(output, "impl {} {{", r#enum).unwrap();
+    writeln!(output, "    pub const ALL: &'static [Self] = &[").unwrap();
+    for code in codes.values() {
+        writeln!(output, "        {}::{},", r#enum, code.name).unwrap();
+    }
+    writeln!(output, "    ];").unwrap();
+
+    writeln!(
+        output,
+        "    pub(crate) const ALIASES: &'static [(&'static str, Self)] = &["
+    )
+    .unwrap();
+    for code in codes.values() {
+        for alias in &code.aliases {
+            writeln!(
+                output,
+                "        (\"{}\", {}::{}),",
+                alias, r#enum, code.name
+            )
+            .unwrap();
+        }
+    }
+    writeln!(output, "    ];").unwrap();
+    writeln!(output, "}}").unwrap();
+
+    output
+}
+
+fn generate_codes(r#enum: &str, codes: &BTreeMap<u16, Code>) -> String {
+    let mut output = String::new();
+
+    writeln!(output, "impl {} {{", r#enum).unwrap();
+    writeln!(output, "    pub(crate) fn to_raw(self) -> u16 {{").unwrap();
+    writeln!(output, "        match self {{").unwrap();
+    for code in codes.values() {
+        writeln!(
+            output,
+            "            {}::{} => {:#06x},",
+            r#enum, code.name, code.code
+        )
+        .unwrap();
+    }
+    writeln!(output, "        }}").unwrap();
+    writeln!(output, "    }}").unwrap();
+
+    writeln!(
+        output,
+        "    pub(crate) fn from_raw(code: u16) -> Option<Self> {{"
+    )
+    .unwrap();
+    writeln!(output, "        let value = match code {{").unwrap();
+    for code in codes.values() {
+        writeln!(
+            output,
+            "            {:#06x} => {}::{},",
+            code.code, r#enum, code.name
+        )
+        .unwrap();
+    }
+    writeln!(output, "            _ => return None,").unwrap();
+    writeln!(output, "        }};").unwrap();
+    writeln!(output, "        Some(value)").unwrap();
+    writeln!(output, "    }}").unwrap();
+    writeln!(output, "}}").unwrap();
+
+    output
+}
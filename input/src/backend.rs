@@ -0,0 +1,86 @@
+use crate::device::{DeviceEvent, DeviceId, DeviceInfo, Led};
+use crate::event::Event;
+use crate::{EventManager, EventWriter};
+use async_trait::async_trait;
+use std::io::Error;
+
+// Input devices of the machine rkvm runs on, which are grabbed so that their events can either be sent elsewhere or
+// written back to local copies of the devices.
+#[async_trait]
+pub trait EventSource: Send {
+    async fn read(&mut self) -> Result<DeviceEvent, Error>;
+
+    // Writes the event to the generic device.
+    async fn write(&mut self, event: Event) -> Result<(), Error>;
+
+    // Writes the event to the local copy of the device it was read from.
+    async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error>;
+
+    // Sets a LED of the physical device.
+    async fn set_led(&mut self, id: DeviceId, led: Led, on: bool) -> Result<(), Error>;
+
+    // Grabs the devices or lets their events reach the rest of the system, if the source supports that.
+    async fn set_grabbed(&mut self, grabbed: bool) -> Result<(), Error>;
+}
+
+// Virtual devices mirroring the input devices of another machine.
+#[async_trait]
+pub trait EventSink: Send {
+    async fn add_device(&mut self, id: DeviceId, info: DeviceInfo) -> Result<(), Error>;
+
+    async fn remove_device(&mut self, id: DeviceId) -> Result<(), Error>;
+
+    // Writes the event to the generic device.
+    async fn write(&mut self, event: Event) -> Result<(), Error>;
+
+    async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error>;
+
+    // Waits for userspace to change a LED of one of the mirrors.
+    async fn read_led(&mut self) -> Result<(DeviceId, Led, bool), Error>;
+}
+
+#[async_trait]
+impl EventSource for EventManager {
+    async fn read(&mut self) -> Result<DeviceEvent, Error> {
+        EventManager::read(self).await
+    }
+
+    async fn write(&mut self, event: Event) -> Result<(), Error> {
+        EventManager::write(self, event).await
+    }
+
+    async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error> {
+        EventManager::write_device(self, id, event).await
+    }
+
+    async fn set_led(&mut self, id: DeviceId, led: Led, on: bool) -> Result<(), Error> {
+        EventManager::set_led(self, id, led, on).await
+    }
+
+    async fn set_grabbed(&mut self, grabbed: bool) -> Result<(), Error> {
+        EventManager::set_grabbed(self, grabbed).await
+    }
+}
+
+#[async_trait]
+impl EventSink for EventWriter {
+    async fn add_device(&mut self, id: DeviceId, info: DeviceInfo) -> Result<(), Error> {
+        EventWriter::add_device(self, id, info).await
+    }
+
+    async fn remove_device(&mut self, id: DeviceId) -> Result<(), Error> {
+        EventWriter::remove_device(self, id).await
+    }
+
+    async fn write(&mut self, event: Event) -> Result<(), Error> {
+        EventWriter::write(self, event).await
+    }
+
+    async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error> {
+        EventWriter::write_device(self, id, event).await
+    }
+
+    async fn read_led(&mut self) -> Result<(DeviceId, Led, bool), Error> {
+        EventWriter::read_led(self).await
+    }
+}
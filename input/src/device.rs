@@ -0,0 +1,76 @@
+use crate::event::{AbsAxis, AbsInfo, Axis, Event, KeyKind};
+use serde::{Deserialize, Serialize};
+use std::io::Error;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static NEXT_DEVICE_ID: AtomicU32 = AtomicU32::new(0);
+
+// Identifies an input device for as long as it stays connected to the machine it's read from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct DeviceId(u32);
+
+impl DeviceId {
+    pub(crate) fn next() -> Self {
+        Self(NEXT_DEVICE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+// Describes an input device well enough for the other side to create a virtual device with the same capabilities.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceInfo {
+    pub name: String,
+    // Where the device is connected, only used to tell devices apart.
+    pub phys: String,
+    pub vendor: u16,
+    pub product: u16,
+    pub version: u16,
+    pub bustype: u16,
+    pub keys: Vec<KeyKind>,
+    pub relative: Vec<Axis>,
+    pub scroll: bool,
+    pub absolute: Vec<(AbsAxis, AbsInfo)>,
+    pub properties: Vec<Property>,
+    pub leds: Vec<Led>,
+    // Whether this is a gamepad or a joystick.
+    pub gamepad: bool,
+}
+
+// A device found by listing input devices of the system.
+#[derive(Debug)]
+pub struct DeviceEntry {
+    pub path: PathBuf,
+    // Opening a device can fail, for example due to permissions.
+    pub info: Result<DeviceInfo, Error>,
+    // Whether this is one of rkvm's own virtual devices, which are never read from.
+    pub own: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Property {
+    Pointer,
+    Direct,
+    ButtonPad,
+    SemiMt,
+    TopButtonPad,
+    PointingStick,
+    Accelerometer,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Led {
+    NumLock,
+    CapsLock,
+    ScrollLock,
+    Compose,
+    Kana,
+}
+
+#[derive(Clone, Debug)]
+pub enum DeviceEvent {
+    Added { id: DeviceId, info: DeviceInfo },
+    Event { id: DeviceId, event: Event },
+    Removed { id: DeviceId },
+    // Userspace changed a LED on the local copy of the device.
+    Led { id: DeviceId, led: Led, on: bool },
+}
@@ -1,19 +1,26 @@
+mod abs;
 mod button;
 mod key;
+mod name;
 
+pub use abs::{AbsAxis, AbsInfo};
 pub use button::Button;
 pub use key::Key;
+pub use name::{parse_chord, ParseKeyError};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Event {
     MouseScroll { delta: i32 },
     MouseMove { axis: Axis, delta: i32 },
     Key { direction: Direction, kind: KeyKind },
+    Absolute { axis: AbsAxis, value: i32 },
+    // Marks the end of a group of events from a device with absolute axes that should be applied at once.
+    Sync,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Axis {
     X,
     Y,
@@ -29,4 +36,6 @@ pub enum Direction {
 pub enum KeyKind {
     Key(Key),
     Button(Button),
+    // A Linux key code rkvm has no name for, such as vendor specific keys.
+    Raw(u16),
 }
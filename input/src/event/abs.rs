@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AbsAxis {
+    X,
+    Y,
+    Z,
+    Rx,
+    Ry,
+    Rz,
+    Throttle,
+    Rudder,
+    Wheel,
+    Gas,
+    Brake,
+    Hat0X,
+    Hat0Y,
+    Hat1X,
+    Hat1Y,
+    Hat2X,
+    Hat2Y,
+    Hat3X,
+    Hat3Y,
+    Pressure,
+    Distance,
+    TiltX,
+    TiltY,
+    ToolWidth,
+    Volume,
+    Profile,
+    Misc,
+    MtSlot,
+    MtTouchMajor,
+    MtTouchMinor,
+    MtWidthMajor,
+    MtWidthMinor,
+    MtOrientation,
+    MtPositionX,
+    MtPositionY,
+    MtToolType,
+    MtBlobId,
+    MtTrackingId,
+    MtPressure,
+    MtDistance,
+    MtToolX,
+    MtToolY,
+}
+
+// Range of values an absolute axis can take, as reported by the device the event originated from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct AbsInfo {
+    pub minimum: i32,
+    pub maximum: i32,
+    pub fuzz: i32,
+    pub flat: i32,
+    pub resolution: i32,
+}
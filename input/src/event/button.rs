@@ -1,124 +1,4 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
-pub enum Button {
-    A,
-    B,
-    Back,
-    Base,
-    Base2,
-    Base3,
-    Base4,
-    Base5,
-    Base6,
-    C,
-    Dead,
-    Digi,
-    DpadDown,
-    DpadLeft,
-    DpadRight,
-    DpadUp,
-    East,
-    Extra,
-    Forward,
-    Gamepad,
-    GearDown,
-    GearUp,
-    Joystick,
-    Left,
-    Middle,
-    Misc,
-    Mode,
-    Mouse,
-    N0,
-    N1,
-    N2,
-    N3,
-    N4,
-    N5,
-    N6,
-    N7,
-    N8,
-    N9,
-    North,
-    Pinkie,
-    Right,
-    Select,
-    Side,
-    South,
-    Start,
-    Stylus,
-    Stylus2,
-    Stylus3,
-    Task,
-    Thumb,
-    Thumb2,
-    Thumbl,
-    Thumbr,
-    Tl,
-    Tl2,
-    ToolAirbrush,
-    ToolBrush,
-    ToolDoubletap,
-    ToolFinger,
-    ToolLens,
-    ToolMouse,
-    ToolPen,
-    ToolPencil,
-    ToolQuadtap,
-    ToolQuinttap,
-    ToolRubber,
-    ToolTripletap,
-    Top,
-    Top2,
-    Touch,
-    Tr,
-    Tr2,
-    Trigger,
-    TriggerHappy,
-    TriggerHappy1,
-    TriggerHappy10,
-    TriggerHappy11,
-    TriggerHappy12,
-    TriggerHappy13,
-    TriggerHappy14,
-    TriggerHappy15,
-    TriggerHappy16,
-    TriggerHappy17,
-    TriggerHappy18,
-    TriggerHappy19,
-    TriggerHappy2,
-    TriggerHappy20,
-    TriggerHappy21,
-    TriggerHappy22,
-    TriggerHappy23,
-    TriggerHappy24,
-    TriggerHappy25,
-    TriggerHappy26,
-    TriggerHappy27,
-    TriggerHappy28,
-    TriggerHappy29,
-    TriggerHappy3,
-    TriggerHappy30,
-    TriggerHappy31,
-    TriggerHappy32,
-    TriggerHappy33,
-    TriggerHappy34,
-    TriggerHappy35,
-    TriggerHappy36,
-    TriggerHappy37,
-    TriggerHappy38,
-    TriggerHappy39,
-    TriggerHappy4,
-    TriggerHappy40,
-    TriggerHappy5,
-    TriggerHappy6,
-    TriggerHappy7,
-    TriggerHappy8,
-    TriggerHappy9,
-    West,
-    Wheel,
-    X,
-    Y,
-    Z,
-}
+// Generated from input-event-codes.h by build.rs.
+include!(concat!(env!("OUT_DIR"), "/button.rs"));
@@ -0,0 +1,335 @@
+use crate::event::{Button, Key, KeyKind};
+use std::error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
+
+// Names people commonly use for keys, in addition to the ones from the kernel headers.
+// Modifiers map to their left variants, since those are the ones present on every keyboard.
+const KEY_ALIASES: &[(&str, Key)] = &[
+    ("Ctrl", Key::LeftCtrl),
+    ("Control", Key::LeftCtrl),
+    ("Alt", Key::LeftAlt),
+    ("AltGr", Key::RightAlt),
+    ("Shift", Key::LeftShift),
+    ("Super", Key::LeftMeta),
+    ("Meta", Key::LeftMeta),
+    ("Win", Key::LeftMeta),
+    ("Windows", Key::LeftMeta),
+    ("Cmd", Key::LeftMeta),
+    ("Escape", Key::Esc),
+    ("Return", Key::Enter),
+    ("Del", Key::Delete),
+    ("Ins", Key::Insert),
+    ("PgUp", Key::PageUp),
+    ("PgDn", Key::PageDown),
+    ("Caps", Key::CapsLock),
+    ("PrintScreen", Key::SysRq),
+    // The digit row, which is what people mean by a bare digit.
+    ("0", Key::N0),
+    ("1", Key::N1),
+    ("2", Key::N2),
+    ("3", Key::N3),
+    ("4", Key::N4),
+    ("5", Key::N5),
+    ("6", Key::N6),
+    ("7", Key::N7),
+    ("8", Key::N8),
+    ("9", Key::N9),
+];
+
+// Prefix used to tell buttons apart from keys with the same name.
+const BUTTON_PREFIX: &str = "Button::";
+const KEY_PREFIX: &str = "Key::";
+// Prefixes of raw Linux key codes, in hexadecimal and decimal.
+const HEX_PREFIX: &str = "0x";
+const CODE_PREFIX: &str = "code:";
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Display for Button {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Display for KeyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyKind::Key(key) => write!(f, "{}", key),
+            KeyKind::Button(button) => write!(f, "{}{}", BUTTON_PREFIX, button),
+            KeyKind::Raw(code) => write!(f, "{:#x}", code),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        parse(name, &key_names())
+    }
+}
+
+impl FromStr for Button {
+    type Err = ParseKeyError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        parse(name, &button_names())
+    }
+}
+
+impl FromStr for KeyKind {
+    type Err = ParseKeyError;
+
+    // Names without a prefix are looked up among keys first, so "Left" is the arrow key and "Button::Left" the mouse
+    // button. Raw Linux key codes are written as "0x1e" or "code:30", bare digits are keys of the digit row.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = strip_prefix(name, BUTTON_PREFIX) {
+            return name.parse().map(KeyKind::Button);
+        }
+
+        if let Some(name) = strip_prefix(name, KEY_PREFIX) {
+            return name.parse().map(KeyKind::Key);
+        }
+
+        let code = if let Some(hex) = strip_prefix(name, HEX_PREFIX) {
+            u16::from_str_radix(hex, 16).ok()
+        } else if let Some(code) = strip_prefix(name, CODE_PREFIX) {
+            code.parse().ok()
+        } else {
+            None
+        };
+
+        if let Some(code) = code {
+            // Resolve codes that have a name, so that they compare equal to the events read from devices.
+            #[cfg(target_os = "linux")]
+            if let Some(kind) = KeyKind::from_raw(code) {
+                return Ok(kind);
+            }
+
+            return Ok(KeyKind::Raw(code));
+        }
+
+        let names = key_names()
+            .into_iter()
+            .map(|(name, key)| (name, KeyKind::Key(key)))
+            .chain(button_names().into_iter().map(|(name, button)| {
+                (
+                    format!("{}{}", BUTTON_PREFIX, name),
+                    KeyKind::Button(button),
+                )
+            }))
+            .collect::<Vec<_>>();
+
+        // Let buttons be written without the prefix too, as long as there's no key with the same name.
+        let names = names
+            .iter()
+            .cloned()
+            .chain(names.iter().filter_map(|(full, kind)| {
+                let name = full.strip_prefix(BUTTON_PREFIX)?;
+                Some((name.to_owned(), *kind))
+            }))
+            .collect::<Vec<_>>();
+
+        parse(name, &names)
+    }
+}
+
+// Parses key combinations such as "Ctrl+Alt+Right".
+pub fn parse_chord(chord: &str) -> Result<Vec<KeyKind>, ParseKeyError> {
+    chord.split('+').map(|name| name.trim().parse()).collect()
+}
+
+#[derive(Debug)]
+pub struct ParseKeyError {
+    name: String,
+    suggestion: Option<String>,
+}
+
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown key \"{}\"", self.name)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean \"{}\"?", suggestion)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for ParseKeyError {}
+
+fn key_names() -> Vec<(String, Key)> {
+    Key::ALL
+        .iter()
+        .map(|key| (key.to_string(), *key))
+        .chain(
+            Key::ALIASES
+                .iter()
+                .map(|(name, key)| (name.to_string(), *key)),
+        )
+        .chain(
+            KEY_ALIASES
+                .iter()
+                .map(|(name, key)| (name.to_string(), *key)),
+        )
+        .collect()
+}
+
+fn button_names() -> Vec<(String, Button)> {
+    Button::ALL
+        .iter()
+        .map(|button| (button.to_string(), *button))
+        .chain(
+            Button::ALIASES
+                .iter()
+                .map(|(name, button)| (name.to_string(), *button)),
+        )
+        .collect()
+}
+
+fn parse<T: Copy>(name: &str, names: &[(String, T)]) -> Result<T, ParseKeyError> {
+    if let Some((_, value)) = names
+        .iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+    {
+        return Ok(*value);
+    }
+
+    // Only suggest names that are reasonably close, otherwise the suggestion is just noise.
+    let suggestion = names
+        .iter()
+        .map(|(candidate, _)| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2.max(name.len() / 3))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone());
+
+    Err(ParseKeyError {
+        name: name.to_owned(),
+        suggestion,
+    })
+}
+
+fn strip_prefix<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    if name.len() >= prefix.len() && name.is_char_boundary(prefix.len()) {
+        let (start, rest) = name.split_at(prefix.len());
+        if start.eq_ignore_ascii_case(prefix) {
+            return Some(rest);
+        }
+    }
+
+    None
+}
+
+// Case insensitive edit distance, which counts swapping two adjacent characters as a single edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a = a.to_ascii_lowercase().chars().collect::<Vec<_>>();
+    let b = b.to_ascii_lowercase().chars().collect::<Vec<_>>();
+
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut value = (rows[i - 1][j - 1] + cost)
+                .min(rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(rows[i - 2][j - 2] + 1);
+            }
+
+            row.push(value);
+        }
+
+        rows.push(row);
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str) -> Result<KeyKind, ParseKeyError> {
+        name.parse()
+    }
+
+    #[test]
+    fn names_are_case_insensitive() {
+        assert_eq!(parse("LeftCtrl").unwrap(), KeyKind::Key(Key::LeftCtrl));
+        assert_eq!(parse("leftctrl").unwrap(), KeyKind::Key(Key::LeftCtrl));
+        assert_eq!(parse("SCROLLLOCK").unwrap(), KeyKind::Key(Key::ScrollLock));
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(parse("Ctrl").unwrap(), KeyKind::Key(Key::LeftCtrl));
+        assert_eq!(parse("win").unwrap(), KeyKind::Key(Key::LeftMeta));
+        assert_eq!(parse("Escape").unwrap(), KeyKind::Key(Key::Esc));
+        assert_eq!(parse("PgDn").unwrap(), KeyKind::Key(Key::PageDown));
+    }
+
+    #[test]
+    fn digits_are_keys_of_the_digit_row() {
+        assert_eq!(parse("1").unwrap(), KeyKind::Key(Key::N1));
+        assert_eq!(parse("0").unwrap(), KeyKind::Key(Key::N0));
+        assert!(parse("30").is_err());
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(parse("Left").unwrap(), KeyKind::Key(Key::Left));
+        assert_eq!(parse("Key::Left").unwrap(), KeyKind::Key(Key::Left));
+        assert_eq!(
+            parse("button::left").unwrap(),
+            KeyKind::Button(Button::Left)
+        );
+        // Buttons don't need the prefix if no key has the same name.
+        assert_eq!(parse("Middle").unwrap(), KeyKind::Button(Button::Middle));
+        assert!(parse("Key::Middle").is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn raw_codes_need_a_prefix() {
+        assert_eq!(parse("0x1e").unwrap(), KeyKind::Key(Key::A));
+        assert_eq!(parse("code:30").unwrap(), KeyKind::Key(Key::A));
+        assert_eq!(parse("0x2fe").unwrap(), KeyKind::Raw(0x2fe));
+        assert!(parse("code:x").is_err());
+    }
+
+    #[test]
+    fn chords() {
+        assert_eq!(
+            parse_chord("Ctrl + 1").unwrap(),
+            vec![KeyKind::Key(Key::LeftCtrl), KeyKind::Key(Key::N1)]
+        );
+        assert_eq!(
+            parse_chord("Ctrl+Alt+Right").unwrap(),
+            vec![
+                KeyKind::Key(Key::LeftCtrl),
+                KeyKind::Key(Key::LeftAlt),
+                KeyKind::Key(Key::Right)
+            ]
+        );
+        assert!(parse_chord("Ctrl+Nope").is_err());
+    }
+
+    #[test]
+    fn close_names_are_suggested() {
+        assert_eq!(
+            parse("LeftCrtl").unwrap_err().to_string(),
+            "Unknown key \"LeftCrtl\", did you mean \"LeftCtrl\"?"
+        );
+        assert_eq!(
+            parse("Xyzzyplugh").unwrap_err().to_string(),
+            "Unknown key \"Xyzzyplugh\""
+        );
+    }
+}
@@ -1,4 +1,8 @@
+mod backend;
+mod device;
 mod event;
+#[cfg(feature = "mock")]
+pub mod mock;
 
 #[cfg(target_os = "linux")]
 mod linux;
@@ -7,9 +11,13 @@ mod linux;
 mod windows;
 
 #[cfg(target_os = "linux")]
-pub use linux::{EventManager, EventWriter};
+pub use linux::{list_devices, EventManager, EventMonitor, EventWriter};
 
 #[cfg(target_os = "windows")]
-pub use windows::{EventManager, EventWriter};
+pub use windows::{list_devices, EventManager, EventMonitor, EventWriter};
 
-pub use event::{Axis, Button, Direction, Event, Key, KeyKind};
+pub use backend::{EventSink, EventSource};
+pub use device::{DeviceEntry, DeviceEvent, DeviceId, DeviceInfo, Led, Property};
+pub use event::{
+    parse_chord, AbsAxis, AbsInfo, Axis, Button, Direction, Event, Key, KeyKind, ParseKeyError,
+};
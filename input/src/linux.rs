@@ -1,9 +1,17 @@
 mod device_id;
+mod device_info;
+mod device_list;
+#[cfg(feature = "ioctl")]
+mod evdev;
 mod event;
 mod event_manager;
+mod event_monitor;
 mod event_reader;
 mod event_writer;
 mod glue;
+mod virtual_device;
 
+pub use device_list::list_devices;
 pub use event_manager::EventManager;
+pub use event_monitor::EventMonitor;
 pub use event_writer::EventWriter;
@@ -1,3 +1,16 @@
+use crate::linux::glue::{self, libevdev};
+use std::ffi::CString;
+
+// IDs of the generic device, which has no original to take them from. Older versions gave them to every device.
 pub const VENDOR: u16 = 0xDEAD;
 pub const PRODUCT: u16 = 0xDEAD;
 pub const VERSION: u16 = 0xDEAD;
+
+// Physical location of every device we create, which is how we tell them apart from real ones. Unlike the IDs, userspace
+// doesn't look it up to apply quirks, so copies of devices can keep the IDs of the original.
+pub const PHYS: &str = "rkvm";
+
+pub unsafe fn set_phys(evdev: *mut libevdev) {
+    let phys = CString::new(PHYS).unwrap();
+    glue::libevdev_set_phys(evdev, phys.as_ptr());
+}
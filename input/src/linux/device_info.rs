@@ -0,0 +1,200 @@
+use crate::device::{DeviceInfo, Led, Property};
+use crate::event::{AbsAxis, AbsInfo, Axis, KeyKind};
+use crate::linux::device_id;
+use crate::linux::glue::{self, libevdev};
+use std::ffi::{CStr, CString};
+use std::io::{Error, ErrorKind};
+use std::ptr;
+
+impl DeviceInfo {
+    pub(crate) unsafe fn from_evdev(evdev: *mut libevdev) -> Self {
+        let name = glue::libevdev_get_name(evdev);
+        let name = if name.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(name).to_string_lossy().into_owned()
+        };
+
+        let phys = glue::libevdev_get_phys(evdev);
+        let phys = if phys.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(phys).to_string_lossy().into_owned()
+        };
+
+        let has = |r#type, code| glue::libevdev_has_event_code(evdev, r#type, code) == 1;
+
+        let keys = (0..=glue::KEY_MAX)
+            .filter(|code| has(glue::EV_KEY, *code))
+            .filter_map(|code| KeyKind::from_raw(code as _))
+            .collect();
+
+        let relative = [(Axis::X, glue::REL_X), (Axis::Y, glue::REL_Y)]
+            .iter()
+            .filter(|(_, code)| has(glue::EV_REL, *code))
+            .map(|(axis, _)| *axis)
+            .collect();
+
+        let absolute = (0..=glue::ABS_MAX)
+            .filter_map(|code| AbsAxis::from_raw(code as _))
+            .filter_map(|axis| {
+                let info = glue::libevdev_get_abs_info(evdev, axis.to_raw() as _);
+                if info.is_null() {
+                    return None;
+                }
+
+                Some((axis, AbsInfo::from_raw(&*info)))
+            })
+            .collect();
+
+        let properties = (0..=glue::INPUT_PROP_MAX)
+            .filter(|property| glue::libevdev_has_property(evdev, *property) == 1)
+            .filter_map(Property::from_raw)
+            .collect();
+
+        let leds = (0..=glue::LED_MAX)
+            .filter(|code| has(glue::EV_LED, *code))
+            .filter_map(Led::from_raw)
+            .collect();
+
+        Self {
+            name,
+            phys,
+            vendor: glue::libevdev_get_id_vendor(evdev) as _,
+            product: glue::libevdev_get_id_product(evdev) as _,
+            version: glue::libevdev_get_id_version(evdev) as _,
+            bustype: glue::libevdev_get_id_bustype(evdev) as _,
+            keys,
+            relative,
+            scroll: has(glue::EV_REL, glue::REL_WHEEL),
+            absolute,
+            properties,
+            leds,
+            gamepad: has(glue::EV_KEY, glue::BTN_GAMEPAD) || has(glue::EV_KEY, glue::BTN_JOYSTICK),
+        }
+    }
+
+    pub(crate) fn is_own(&self) -> bool {
+        // Older versions of rkvm didn't set the physical location and gave every device the IDs of the generic one.
+        self.phys == device_id::PHYS
+            || (self.vendor == device_id::VENDOR
+                && self.product == device_id::PRODUCT
+                && self.version == device_id::VERSION)
+    }
+
+    pub(crate) unsafe fn setup_evdev(&self, evdev: *mut libevdev) -> Result<(), Error> {
+        // Keep the original identity, since userspace uses it to apply quirks and games to look up button mappings.
+        // The physical location of the original doesn't mean anything here, so it's replaced with our own.
+        let name = CString::new(self.name.as_str())
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        glue::libevdev_set_name(evdev, name.as_ptr());
+        device_id::set_phys(evdev);
+        glue::libevdev_set_id_vendor(evdev, self.vendor as _);
+        glue::libevdev_set_id_product(evdev, self.product as _);
+        glue::libevdev_set_id_version(evdev, self.version as _);
+        glue::libevdev_set_id_bustype(evdev, self.bustype as _);
+
+        let relative = self.relative.iter().map(|axis| match axis {
+            Axis::X => glue::REL_X,
+            Axis::Y => glue::REL_Y,
+        });
+
+        let codes = std::iter::once((glue::EV_SYN, glue::SYN_REPORT, None))
+            .chain(
+                self.keys
+                    .iter()
+                    .map(|kind| (glue::EV_KEY, kind.to_raw() as _, None)),
+            )
+            .chain(relative.map(|code| (glue::EV_REL, code, None)))
+            .chain(
+                Some((glue::EV_REL, glue::REL_WHEEL, None))
+                    .into_iter()
+                    .filter(|_| self.scroll),
+            )
+            .chain(
+                self.absolute
+                    .iter()
+                    .map(|(axis, info)| (glue::EV_ABS, axis.to_raw() as _, Some(info.to_raw()))),
+            )
+            .chain(
+                self.leds
+                    .iter()
+                    .map(|led| (glue::EV_LED, led.to_raw(), None)),
+            );
+
+        for (r#type, code, info) in codes {
+            let data = info
+                .as_ref()
+                .map(|info| info as *const _ as *const _)
+                .unwrap_or(ptr::null());
+
+            let ret = glue::libevdev_enable_event_code(evdev, r#type, code, data);
+            if ret < 0 {
+                return Err(Error::from_raw_os_error(-ret));
+            }
+        }
+
+        for property in &self.properties {
+            let ret = glue::libevdev_enable_property(evdev, property.to_raw());
+            if ret < 0 {
+                return Err(Error::from_raw_os_error(-ret));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Property {
+    pub(crate) fn to_raw(self) -> u32 {
+        match self {
+            Property::Pointer => glue::INPUT_PROP_POINTER,
+            Property::Direct => glue::INPUT_PROP_DIRECT,
+            Property::ButtonPad => glue::INPUT_PROP_BUTTONPAD,
+            Property::SemiMt => glue::INPUT_PROP_SEMI_MT,
+            Property::TopButtonPad => glue::INPUT_PROP_TOPBUTTONPAD,
+            Property::PointingStick => glue::INPUT_PROP_POINTING_STICK,
+            Property::Accelerometer => glue::INPUT_PROP_ACCELEROMETER,
+        }
+    }
+
+    pub(crate) fn from_raw(property: u32) -> Option<Self> {
+        let property = match property {
+            glue::INPUT_PROP_POINTER => Property::Pointer,
+            glue::INPUT_PROP_DIRECT => Property::Direct,
+            glue::INPUT_PROP_BUTTONPAD => Property::ButtonPad,
+            glue::INPUT_PROP_SEMI_MT => Property::SemiMt,
+            glue::INPUT_PROP_TOPBUTTONPAD => Property::TopButtonPad,
+            glue::INPUT_PROP_POINTING_STICK => Property::PointingStick,
+            glue::INPUT_PROP_ACCELEROMETER => Property::Accelerometer,
+            _ => return None,
+        };
+
+        Some(property)
+    }
+}
+
+impl Led {
+    pub(crate) fn to_raw(self) -> u32 {
+        match self {
+            Led::NumLock => glue::LED_NUML,
+            Led::CapsLock => glue::LED_CAPSL,
+            Led::ScrollLock => glue::LED_SCROLLL,
+            Led::Compose => glue::LED_COMPOSE,
+            Led::Kana => glue::LED_KANA,
+        }
+    }
+
+    pub(crate) fn from_raw(led: u32) -> Option<Self> {
+        let led = match led {
+            glue::LED_NUML => Led::NumLock,
+            glue::LED_CAPSL => Led::CapsLock,
+            glue::LED_SCROLLL => Led::ScrollLock,
+            glue::LED_COMPOSE => Led::Compose,
+            glue::LED_KANA => Led::Kana,
+            _ => return None,
+        };
+
+        Some(led)
+    }
+}
@@ -0,0 +1,57 @@
+use crate::device::{DeviceEntry, DeviceInfo};
+use crate::linux::event_manager::{self, EVENT_PATH};
+use crate::linux::glue;
+use std::fs::File;
+use std::io::Error;
+use std::mem::MaybeUninit;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use tokio::fs;
+
+// Lists the same devices the event manager would try to open, without grabbing them.
+pub async fn list_devices() -> Result<Vec<DeviceEntry>, Error> {
+    let mut paths = Vec::new();
+    let mut read_dir = fs::read_dir(EVENT_PATH).await?;
+    while let Some(entry) = read_dir.next_entry().await? {
+        let path = entry.path();
+        if event_manager::is_event_file(&path) {
+            paths.push(path);
+        }
+    }
+
+    // Sort event2 before event10.
+    paths.sort_by_key(|path| (path.as_os_str().len(), path.clone()));
+
+    let entries = tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .map(|path| {
+                let info = read_info(&path);
+                let own = info.as_ref().map(DeviceInfo::is_own).unwrap_or(false);
+
+                DeviceEntry { path, info, own }
+            })
+            .collect()
+    })
+    .await?;
+
+    Ok(entries)
+}
+
+fn read_info(path: &Path) -> Result<DeviceInfo, Error> {
+    let file = File::open(path)?;
+
+    let mut evdev = MaybeUninit::uninit();
+    let ret = unsafe { glue::libevdev_new_from_fd(file.as_raw_fd(), evdev.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(Error::from_raw_os_error(-ret));
+    }
+
+    unsafe {
+        let evdev = evdev.assume_init();
+        let info = DeviceInfo::from_evdev(evdev);
+        glue::libevdev_free(evdev);
+
+        Ok(info)
+    }
+}
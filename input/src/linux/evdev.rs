@@ -0,0 +1,1110 @@
+// A pure Rust implementation of the parts of libevdev we use, talking to evdev and uinput through ioctls directly.
+//
+// It keeps the names and calling conventions of libevdev, including returning negated errno values, so that it can
+// stand in for the bindings in glue.rs without the rest of the backend noticing. This way rkvm can be built without
+// libevdev and clang, which is mostly useful when cross compiling.
+
+#![allow(non_camel_case_types, non_upper_case_globals)]
+
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::io::Error;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_long, c_uint, c_ulong, c_void};
+use std::path::Path;
+use std::ptr;
+
+#[allow(dead_code)]
+mod codes {
+    include!(concat!(env!("OUT_DIR"), "/event_codes.rs"));
+}
+
+pub use codes::*;
+
+// From linux/input.h, which isn't vendored since this is the only define we need from it.
+pub const BUS_USB: u32 = 0x03;
+
+pub const libevdev_read_flag_LIBEVDEV_READ_FLAG_SYNC: c_uint = 1;
+pub const libevdev_read_flag_LIBEVDEV_READ_FLAG_NORMAL: c_uint = 2;
+pub const libevdev_read_status_LIBEVDEV_READ_STATUS_SUCCESS: c_uint = 0;
+pub const libevdev_read_status_LIBEVDEV_READ_STATUS_SYNC: c_uint = 1;
+pub const libevdev_grab_mode_LIBEVDEV_GRAB: c_uint = 3;
+pub const libevdev_grab_mode_LIBEVDEV_UNGRAB: c_uint = 4;
+pub const libevdev_led_value_LIBEVDEV_LED_ON: c_uint = 3;
+pub const libevdev_led_value_LIBEVDEV_LED_OFF: c_uint = 4;
+pub const libevdev_uinput_open_mode_LIBEVDEV_UINPUT_OPEN_MANAGED: c_int = -2;
+
+// The kernel uses longs even where time_t is 64 bits on 32 bit architectures.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct timeval {
+    pub tv_sec: c_long,
+    pub tv_usec: c_long,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct input_event {
+    pub time: timeval,
+    pub type_: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct input_absinfo {
+    pub value: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub fuzz: i32,
+    pub flat: i32,
+    pub resolution: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct input_id {
+    bustype: u16,
+    vendor: u16,
+    product: u16,
+    version: u16,
+}
+
+const UINPUT_MAX_NAME_SIZE: usize = 80;
+
+#[repr(C)]
+struct uinput_setup {
+    id: input_id,
+    name: [c_char; UINPUT_MAX_NAME_SIZE],
+    ff_effects_max: u32,
+}
+
+#[repr(C)]
+struct uinput_abs_setup {
+    code: u16,
+    absinfo: input_absinfo,
+}
+
+// Request numbers as built by the _IOC macro, which has a different layout on a few architectures.
+#[cfg(not(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc64"
+)))]
+mod dir {
+    pub const NONE: u32 = 0;
+    pub const WRITE: u32 = 1;
+    pub const READ: u32 = 2;
+    pub const SIZE_BITS: u32 = 14;
+}
+
+#[cfg(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc64"
+))]
+mod dir {
+    pub const NONE: u32 = 1;
+    pub const READ: u32 = 2;
+    pub const WRITE: u32 = 4;
+    pub const SIZE_BITS: u32 = 13;
+}
+
+const fn ioc(dir: u32, r#type: u8, nr: u32, size: usize) -> c_ulong {
+    ((dir << (16 + dir::SIZE_BITS)) | ((size as u32) << 16) | ((r#type as u32) << 8) | nr)
+        as c_ulong
+}
+
+const EVIOCGID: c_ulong = ioc(dir::READ, b'E', 0x02, mem::size_of::<input_id>());
+const EVIOCGRAB: c_ulong = ioc(dir::WRITE, b'E', 0x90, mem::size_of::<c_int>());
+
+const fn eviocgname(len: usize) -> c_ulong {
+    ioc(dir::READ, b'E', 0x06, len)
+}
+
+const fn eviocgphys(len: usize) -> c_ulong {
+    ioc(dir::READ, b'E', 0x07, len)
+}
+
+const fn eviocgprop(len: usize) -> c_ulong {
+    ioc(dir::READ, b'E', 0x09, len)
+}
+
+const fn eviocgmtslots(len: usize) -> c_ulong {
+    ioc(dir::READ, b'E', 0x0a, len)
+}
+
+const fn eviocgbit(r#type: u32, len: usize) -> c_ulong {
+    ioc(dir::READ, b'E', 0x20 + r#type, len)
+}
+
+const fn eviocgabs(code: u32) -> c_ulong {
+    ioc(
+        dir::READ,
+        b'E',
+        0x40 + code,
+        mem::size_of::<input_absinfo>(),
+    )
+}
+
+const UI_DEV_CREATE: c_ulong = ioc(dir::NONE, b'U', 1, 0);
+const UI_DEV_DESTROY: c_ulong = ioc(dir::NONE, b'U', 2, 0);
+const UI_DEV_SETUP: c_ulong = ioc(dir::WRITE, b'U', 3, mem::size_of::<uinput_setup>());
+const UI_ABS_SETUP: c_ulong = ioc(dir::WRITE, b'U', 4, mem::size_of::<uinput_abs_setup>());
+const UI_SET_EVBIT: c_ulong = ioc(dir::WRITE, b'U', 100, mem::size_of::<c_int>());
+const UI_SET_PHYS: c_ulong = ioc(dir::WRITE, b'U', 108, mem::size_of::<*const c_char>());
+const UI_SET_PROPBIT: c_ulong = ioc(dir::WRITE, b'U', 110, mem::size_of::<c_int>());
+
+const fn ui_get_sysname(len: usize) -> c_ulong {
+    ioc(dir::READ, b'U', 44, len)
+}
+
+// Types which have codes, along with the number of the uinput request that enables them.
+const CODE_TYPES: &[(u32, u32)] = &[
+    (EV_KEY, 101),
+    (EV_REL, 102),
+    (EV_ABS, 103),
+    (EV_MSC, 104),
+    (EV_LED, 105),
+    (EV_SND, 106),
+    (EV_FF, 107),
+    (EV_SW, 109),
+];
+
+// Types which have a state, along with the number of the request that reads it.
+const STATE_TYPES: &[(u32, u32)] = &[(EV_KEY, 0x18), (EV_LED, 0x19), (EV_SW, 0x1b)];
+
+// Axes which have a value for each slot.
+const MT_AXES: usize = (ABS_MT_TOOL_Y - ABS_MT_TOUCH_MAJOR + 1) as usize;
+
+const LONG_BITS: usize = mem::size_of::<c_ulong>() * 8;
+
+// The kernel exchanges bitmaps as arrays of longs.
+const fn longs(max: u32) -> usize {
+    (max as usize + LONG_BITS) / LONG_BITS
+}
+
+// Keys have the most codes of all types.
+type CodeBits = [c_ulong; longs(KEY_MAX)];
+
+fn test_bit(bits: &[c_ulong], bit: u32) -> bool {
+    let bit = bit as usize;
+    bits.get(bit / LONG_BITS)
+        .map(|long| long & (1 << (bit % LONG_BITS)) != 0)
+        .unwrap_or(false)
+}
+
+fn set_bit(bits: &mut [c_ulong], bit: u32) -> bool {
+    let bit = bit as usize;
+    match bits.get_mut(bit / LONG_BITS) {
+        Some(long) => {
+            *long |= 1 << (bit % LONG_BITS);
+            true
+        }
+        None => false,
+    }
+}
+
+fn clear_bit(bits: &mut [c_ulong], bit: u32) {
+    let bit = bit as usize;
+    if let Some(long) = bits.get_mut(bit / LONG_BITS) {
+        *long &= !(1 << (bit % LONG_BITS));
+    }
+}
+
+pub struct libevdev {
+    // The device this was created from, or -1.
+    fd: c_int,
+    name: Option<CString>,
+    phys: Option<CString>,
+    id: input_id,
+    types: [c_ulong; longs(EV_MAX)],
+    codes: [CodeBits; EV_MAX as usize + 1],
+    properties: [c_ulong; longs(INPUT_PROP_MAX)],
+    absinfo: [input_absinfo; ABS_MAX as usize + 1],
+    // Which keys, LEDs and switches are on.
+    state: [CodeBits; EV_MAX as usize + 1],
+    // Values of multitouch axes of each slot.
+    slots: Vec<[i32; MT_AXES]>,
+    current_slot: c_int,
+    // Events describing how the state changed while events were dropped.
+    queue: VecDeque<input_event>,
+}
+
+impl libevdev {
+    fn new() -> Self {
+        Self {
+            fd: -1,
+            name: None,
+            phys: None,
+            id: input_id::default(),
+            types: [0; longs(EV_MAX)],
+            codes: [[0; longs(KEY_MAX)]; EV_MAX as usize + 1],
+            properties: [0; longs(INPUT_PROP_MAX)],
+            absinfo: [input_absinfo::default(); ABS_MAX as usize + 1],
+            state: [[0; longs(KEY_MAX)]; EV_MAX as usize + 1],
+            slots: Vec::new(),
+            current_slot: -1,
+            queue: VecDeque::new(),
+        }
+    }
+
+    // Reads the description of the device from the kernel.
+    unsafe fn set_fd(&mut self, fd: c_int) -> Result<(), c_int> {
+        self.name = Some(get_string(fd, eviocgname)?);
+        self.phys = match get_string(fd, eviocgphys) {
+            Ok(phys) => Some(phys),
+            // The device has no physical location.
+            Err(err) if err == -libc::ENOENT => None,
+            Err(err) => return Err(err),
+        };
+
+        check(libc::ioctl(
+            fd,
+            EVIOCGID as _,
+            &mut self.id as *mut input_id,
+        ))?;
+        check(libc::ioctl(
+            fd,
+            eviocgbit(0, mem::size_of_val(&self.types)) as _,
+            self.types.as_mut_ptr(),
+        ))?;
+
+        for (r#type, _) in CODE_TYPES {
+            if !self.has_type(*r#type) {
+                continue;
+            }
+
+            let bits = &mut self.codes[*r#type as usize];
+            check(libc::ioctl(
+                fd,
+                eviocgbit(*r#type, mem::size_of_val(bits)) as _,
+                bits.as_mut_ptr(),
+            ))?;
+        }
+
+        // Kernels older than 3.7 don't know about properties.
+        match check(libc::ioctl(
+            fd,
+            eviocgprop(mem::size_of_val(&self.properties)) as _,
+            self.properties.as_mut_ptr(),
+        )) {
+            Err(err) if err == -libc::EINVAL => {}
+            result => {
+                result?;
+            }
+        }
+
+        for code in 0..=ABS_MAX {
+            if self.has(EV_ABS, code) {
+                self.absinfo[code as usize] = get_absinfo(fd, code)?;
+            }
+        }
+
+        self.fd = fd;
+
+        // There's nothing to compare the state with yet.
+        self.sync_state()?;
+        self.queue.clear();
+
+        Ok(())
+    }
+
+    // Keeps track of the state of the device.
+    fn update(&mut self, event: &input_event) {
+        let (r#type, code) = (event.type_ as u32, event.code as u32);
+        if !self.has(r#type, code) {
+            return;
+        }
+
+        if STATE_TYPES.iter().any(|(other, _)| *other == r#type) {
+            let state = &mut self.state[r#type as usize];
+            if event.value != 0 {
+                set_bit(state, code);
+            } else {
+                clear_bit(state, code);
+            }
+        }
+
+        if r#type != EV_ABS {
+            return;
+        }
+
+        self.absinfo[code as usize].value = event.value;
+        if code == ABS_MT_SLOT {
+            self.current_slot = event.value;
+        } else if self.is_slotted(code) {
+            if let Some(slot) = self.slots.get_mut(self.current_slot as usize) {
+                slot[(code - ABS_MT_TOUCH_MAJOR) as usize] = event.value;
+            }
+        }
+    }
+
+    fn is_slotted(&self, code: u32) -> bool {
+        (ABS_MT_SLOT..=ABS_MT_TOOL_Y).contains(&code) && self.has(EV_ABS, ABS_MT_SLOT)
+    }
+
+    fn push(&mut self, r#type: u32, code: u32, value: i32) {
+        self.queue.push_back(new_event(r#type, code, value));
+    }
+
+    // Throws away the events the kernel still has, since they describe changes from an unknown state, and queues
+    // events for whatever changed since the state was last known instead.
+    unsafe fn resync(&mut self) -> Result<(), c_int> {
+        let mut events = [new_event(0, 0, 0); 64];
+        loop {
+            let ret = libc::read(
+                self.fd,
+                events.as_mut_ptr() as *mut c_void,
+                mem::size_of_val(&events),
+            );
+
+            if ret <= 0 {
+                break;
+            }
+        }
+
+        self.sync_state()
+    }
+
+    unsafe fn sync_state(&mut self) -> Result<(), c_int> {
+        for (r#type, nr) in STATE_TYPES {
+            if !self.has_type(*r#type) {
+                continue;
+            }
+
+            let mut state: CodeBits = [0; longs(KEY_MAX)];
+            check(libc::ioctl(
+                self.fd,
+                ioc(dir::READ, b'E', *nr, mem::size_of_val(&state)) as _,
+                state.as_mut_ptr(),
+            ))?;
+
+            self.diff_state(*r#type, state);
+        }
+
+        for code in 0..=ABS_MAX {
+            if !self.has(EV_ABS, code) || self.is_slotted(code) {
+                continue;
+            }
+
+            let absinfo = get_absinfo(self.fd, code)?;
+            self.diff_absinfo(code, absinfo);
+        }
+
+        if self.has(EV_ABS, ABS_MT_SLOT) {
+            self.sync_slots()?;
+        }
+
+        if !self.queue.is_empty() {
+            self.push(EV_SYN, SYN_REPORT, 0);
+        }
+
+        Ok(())
+    }
+
+    unsafe fn sync_slots(&mut self) -> Result<(), c_int> {
+        let count = (self.absinfo[ABS_MT_SLOT as usize].maximum + 1).max(0) as usize;
+        let mut slots = vec![[0; MT_AXES]; count];
+
+        // The request takes the code followed by room for the value of each slot.
+        let mut values = vec![0; count + 1];
+        for code in ABS_MT_TOUCH_MAJOR..=ABS_MT_TOOL_Y {
+            if !self.has(EV_ABS, code) {
+                continue;
+            }
+
+            values[0] = code as i32;
+            check(libc::ioctl(
+                self.fd,
+                eviocgmtslots(mem::size_of_val(values.as_slice())) as _,
+                values.as_mut_ptr(),
+            ))?;
+
+            for (slot, value) in slots.iter_mut().zip(&values[1..]) {
+                slot[(code - ABS_MT_TOUCH_MAJOR) as usize] = *value;
+            }
+        }
+
+        let absinfo = get_absinfo(self.fd, ABS_MT_SLOT)?;
+        self.diff_slots(slots, absinfo);
+
+        Ok(())
+    }
+
+    // Queues events for the keys, LEDs or switches which are on or off in the given state but weren't before.
+    fn diff_state(&mut self, r#type: u32, state: CodeBits) {
+        for code in 0..=KEY_MAX {
+            let on = test_bit(&state, code);
+            if self.has(r#type, code) && on != test_bit(&self.state[r#type as usize], code) {
+                self.push(r#type, code, on as _);
+            }
+        }
+
+        self.state[r#type as usize] = state;
+    }
+
+    fn diff_absinfo(&mut self, code: u32, absinfo: input_absinfo) {
+        if absinfo.value != self.absinfo[code as usize].value {
+            self.push(EV_ABS, code, absinfo.value);
+        }
+
+        self.absinfo[code as usize] = absinfo;
+    }
+
+    // Takes the values of each slot and the slot axis as the kernel has them.
+    fn diff_slots(&mut self, slots: Vec<[i32; MT_AXES]>, absinfo: input_absinfo) {
+        self.slots.resize(slots.len(), [0; MT_AXES]);
+        let mut changed = false;
+        for (index, slot) in slots.iter().enumerate() {
+            let mut selected = false;
+            for (axis, value) in slot.iter().enumerate() {
+                if *value == self.slots[index][axis] {
+                    continue;
+                }
+
+                if !selected {
+                    self.push(EV_ABS, ABS_MT_SLOT, index as _);
+                    selected = true;
+                    changed = true;
+                }
+
+                self.push(EV_ABS, ABS_MT_TOUCH_MAJOR + axis as u32, *value);
+            }
+        }
+
+        self.slots = slots;
+
+        // Leave the slot where the kernel has it.
+        if changed || absinfo.value != self.current_slot {
+            self.push(EV_ABS, ABS_MT_SLOT, absinfo.value);
+        }
+
+        self.absinfo[ABS_MT_SLOT as usize] = absinfo;
+        self.current_slot = absinfo.value;
+    }
+
+    fn has_type(&self, r#type: u32) -> bool {
+        test_bit(&self.types, r#type)
+    }
+
+    fn has(&self, r#type: u32, code: u32) -> bool {
+        self.has_type(r#type)
+            && self
+                .codes
+                .get(r#type as usize)
+                .map(|bits| test_bit(bits, code))
+                .unwrap_or(false)
+    }
+
+    // Tells uinput to create a device like this one.
+    unsafe fn setup_uinput(&self, fd: c_int) -> Result<(), c_int> {
+        for r#type in 0..=EV_MAX {
+            if !self.has_type(r#type) {
+                continue;
+            }
+
+            check(libc::ioctl(fd, UI_SET_EVBIT as _, r#type as c_ulong))?;
+
+            let request = match CODE_TYPES.iter().find(|(other, _)| *other == r#type) {
+                Some((_, nr)) => ioc(dir::WRITE, b'U', *nr, mem::size_of::<c_int>()),
+                None => continue,
+            };
+
+            for code in 0..=KEY_MAX {
+                if !self.has(r#type, code) {
+                    continue;
+                }
+
+                check(libc::ioctl(fd, request as _, code as c_ulong))?;
+
+                if r#type == EV_ABS {
+                    let setup = uinput_abs_setup {
+                        code: code as _,
+                        absinfo: self.absinfo[code as usize],
+                    };
+
+                    check(libc::ioctl(
+                        fd,
+                        UI_ABS_SETUP as _,
+                        &setup as *const uinput_abs_setup,
+                    ))?;
+                }
+            }
+        }
+
+        for property in 0..=INPUT_PROP_MAX {
+            if test_bit(&self.properties, property) {
+                check(libc::ioctl(fd, UI_SET_PROPBIT as _, property as c_ulong))?;
+            }
+        }
+
+        if let Some(phys) = &self.phys {
+            check(libc::ioctl(fd, UI_SET_PHYS as _, phys.as_ptr()))?;
+        }
+
+        let mut setup = uinput_setup {
+            id: self.id,
+            name: [0; UINPUT_MAX_NAME_SIZE],
+            ff_effects_max: if self.has_type(EV_FF) { 10 } else { 0 },
+        };
+
+        // Longer names are cut off, keeping the terminating zero.
+        let name = self
+            .name
+            .as_ref()
+            .map(|name| name.as_bytes())
+            .unwrap_or(&[]);
+        for (dst, src) in setup.name[..UINPUT_MAX_NAME_SIZE - 1].iter_mut().zip(name) {
+            *dst = *src as _;
+        }
+
+        check(libc::ioctl(
+            fd,
+            UI_DEV_SETUP as _,
+            &setup as *const uinput_setup,
+        ))?;
+        check(libc::ioctl(fd, UI_DEV_CREATE as _))?;
+
+        Ok(())
+    }
+}
+
+// Turns a failed call into the negated errno.
+fn check(ret: c_int) -> Result<c_int, c_int> {
+    if ret < 0 {
+        return Err(-Error::last_os_error().raw_os_error().unwrap_or(libc::EIO));
+    }
+
+    Ok(ret)
+}
+
+unsafe fn get_string(fd: c_int, request: fn(usize) -> c_ulong) -> Result<CString, c_int> {
+    // The kernel doesn't terminate strings that don't fit.
+    let mut buffer = [0 as c_char; 256];
+    check(libc::ioctl(
+        fd,
+        request(buffer.len() - 1) as _,
+        buffer.as_mut_ptr(),
+    ))?;
+
+    Ok(CStr::from_ptr(buffer.as_ptr()).to_owned())
+}
+
+unsafe fn get_absinfo(fd: c_int, code: u32) -> Result<input_absinfo, c_int> {
+    let mut absinfo = input_absinfo::default();
+    check(libc::ioctl(
+        fd,
+        eviocgabs(code) as _,
+        &mut absinfo as *mut input_absinfo,
+    ))?;
+
+    Ok(absinfo)
+}
+
+unsafe fn write_events(fd: c_int, events: &[input_event]) -> c_int {
+    let size = mem::size_of_val(events);
+    let ret = libc::write(fd, events.as_ptr() as *const c_void, size);
+    if ret < 0 {
+        return -Error::last_os_error().raw_os_error().unwrap_or(libc::EIO);
+    }
+
+    if ret as usize != size {
+        return -libc::EIO;
+    }
+
+    0
+}
+
+fn new_event(r#type: u32, code: u32, value: i32) -> input_event {
+    input_event {
+        time: timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        },
+        type_: r#type as _,
+        code: code as _,
+        value,
+    }
+}
+
+fn into_ret(result: Result<(), c_int>) -> c_int {
+    result.err().unwrap_or(0)
+}
+
+pub unsafe fn libevdev_new() -> *mut libevdev {
+    Box::into_raw(Box::new(libevdev::new()))
+}
+
+pub unsafe fn libevdev_new_from_fd(fd: c_int, dev: *mut *mut libevdev) -> c_int {
+    let mut evdev = Box::new(libevdev::new());
+    if let Err(err) = evdev.set_fd(fd) {
+        return err;
+    }
+
+    *dev = Box::into_raw(evdev);
+    0
+}
+
+pub unsafe fn libevdev_free(dev: *mut libevdev) {
+    if !dev.is_null() {
+        drop(Box::from_raw(dev));
+    }
+}
+
+pub unsafe fn libevdev_grab(dev: *mut libevdev, grab: c_uint) -> c_int {
+    let grab = (grab == libevdev_grab_mode_LIBEVDEV_GRAB) as c_ulong;
+    into_ret(check(libc::ioctl((*dev).fd, EVIOCGRAB as _, grab)).map(|_| ()))
+}
+
+// Once SYN_DROPPED is read, the changes to the state are handed out in the sync mode, as with libevdev.
+pub unsafe fn libevdev_next_event(
+    dev: *mut libevdev,
+    flags: c_uint,
+    ev: *mut input_event,
+) -> c_int {
+    let dev = &mut *dev;
+    if flags & libevdev_read_flag_LIBEVDEV_READ_FLAG_SYNC != 0 {
+        return match dev.queue.pop_front() {
+            Some(event) => {
+                *ev = event;
+                libevdev_read_status_LIBEVDEV_READ_STATUS_SYNC as _
+            }
+            None => -libc::EAGAIN,
+        };
+    }
+
+    // Changes that weren't asked for are gone once reading continues normally.
+    dev.queue.clear();
+
+    let size = mem::size_of::<input_event>();
+    let ret = libc::read(dev.fd, ev as *mut c_void, size);
+    if ret < 0 {
+        return -Error::last_os_error().raw_os_error().unwrap_or(libc::EIO);
+    }
+
+    // Nothing is read once the device is gone.
+    if ret as usize != size {
+        return -libc::ENODEV;
+    }
+
+    let event = *ev;
+    if event.type_ as u32 == EV_SYN && event.code as u32 == SYN_DROPPED {
+        if let Err(err) = dev.resync() {
+            return err;
+        }
+
+        return libevdev_read_status_LIBEVDEV_READ_STATUS_SYNC as _;
+    }
+
+    dev.update(&event);
+    libevdev_read_status_LIBEVDEV_READ_STATUS_SUCCESS as _
+}
+
+pub unsafe fn libevdev_get_current_slot(dev: *const libevdev) -> c_int {
+    (*dev).current_slot
+}
+
+pub unsafe fn libevdev_get_name(dev: *const libevdev) -> *const c_char {
+    (*dev)
+        .name
+        .as_ref()
+        .map(|name| name.as_ptr())
+        .unwrap_or(ptr::null())
+}
+
+pub unsafe fn libevdev_set_name(dev: *mut libevdev, name: *const c_char) {
+    (*dev).name = if name.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(name).to_owned())
+    };
+}
+
+pub unsafe fn libevdev_get_phys(dev: *const libevdev) -> *const c_char {
+    (*dev)
+        .phys
+        .as_ref()
+        .map(|phys| phys.as_ptr())
+        .unwrap_or(ptr::null())
+}
+
+pub unsafe fn libevdev_set_phys(dev: *mut libevdev, phys: *const c_char) {
+    (*dev).phys = if phys.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(phys).to_owned())
+    };
+}
+
+pub unsafe fn libevdev_get_id_vendor(dev: *const libevdev) -> c_int {
+    (*dev).id.vendor as _
+}
+
+pub unsafe fn libevdev_get_id_product(dev: *const libevdev) -> c_int {
+    (*dev).id.product as _
+}
+
+pub unsafe fn libevdev_get_id_version(dev: *const libevdev) -> c_int {
+    (*dev).id.version as _
+}
+
+pub unsafe fn libevdev_get_id_bustype(dev: *const libevdev) -> c_int {
+    (*dev).id.bustype as _
+}
+
+pub unsafe fn libevdev_set_id_vendor(dev: *mut libevdev, vendor: c_int) {
+    (*dev).id.vendor = vendor as _;
+}
+
+pub unsafe fn libevdev_set_id_product(dev: *mut libevdev, product: c_int) {
+    (*dev).id.product = product as _;
+}
+
+pub unsafe fn libevdev_set_id_version(dev: *mut libevdev, version: c_int) {
+    (*dev).id.version = version as _;
+}
+
+pub unsafe fn libevdev_set_id_bustype(dev: *mut libevdev, bustype: c_int) {
+    (*dev).id.bustype = bustype as _;
+}
+
+pub unsafe fn libevdev_has_event_type(dev: *const libevdev, r#type: c_uint) -> c_int {
+    (*dev).has_type(r#type) as _
+}
+
+pub unsafe fn libevdev_has_event_code(dev: *const libevdev, r#type: c_uint, code: c_uint) -> c_int {
+    (*dev).has(r#type, code) as _
+}
+
+// Whether a key, LED or switch is on, or the value of an axis.
+pub unsafe fn libevdev_get_event_value(
+    dev: *const libevdev,
+    r#type: c_uint,
+    code: c_uint,
+) -> c_int {
+    let dev = &*dev;
+    if !dev.has(r#type, code) {
+        return 0;
+    }
+
+    if r#type == EV_ABS {
+        return dev.absinfo[code as usize].value;
+    }
+
+    test_bit(&dev.state[r#type as usize], code) as _
+}
+
+pub unsafe fn libevdev_has_property(dev: *const libevdev, property: c_uint) -> c_int {
+    test_bit(&(*dev).properties, property) as _
+}
+
+pub unsafe fn libevdev_get_abs_info(dev: *const libevdev, code: c_uint) -> *const input_absinfo {
+    let dev = &*dev;
+    if !dev.has(EV_ABS, code) {
+        return ptr::null();
+    }
+
+    &dev.absinfo[code as usize]
+}
+
+pub unsafe fn libevdev_enable_event_type(dev: *mut libevdev, r#type: c_uint) -> c_int {
+    if r#type > EV_MAX || !set_bit(&mut (*dev).types, r#type) {
+        return -1;
+    }
+
+    0
+}
+
+pub unsafe fn libevdev_disable_event_type(dev: *mut libevdev, r#type: c_uint) -> c_int {
+    // Like libevdev, refuse to disable EV_SYN.
+    if r#type > EV_MAX || r#type == EV_SYN {
+        return -1;
+    }
+
+    clear_bit(&mut (*dev).types, r#type);
+    0
+}
+
+// Absolute axes need their input_absinfo as the data.
+pub unsafe fn libevdev_enable_event_code(
+    dev: *mut libevdev,
+    r#type: c_uint,
+    code: c_uint,
+    data: *const c_void,
+) -> c_int {
+    let dev = &mut *dev;
+    if r#type > EV_MAX || (r#type == EV_ABS && (code > ABS_MAX || data.is_null())) {
+        return -1;
+    }
+
+    if !set_bit(&mut dev.codes[r#type as usize], code) {
+        return -1;
+    }
+
+    if r#type == EV_ABS {
+        dev.absinfo[code as usize] = *(data as *const input_absinfo);
+    }
+
+    libevdev_enable_event_type(dev, r#type)
+}
+
+pub unsafe fn libevdev_enable_property(dev: *mut libevdev, property: c_uint) -> c_int {
+    if property > INPUT_PROP_MAX || !set_bit(&mut (*dev).properties, property) {
+        return -1;
+    }
+
+    0
+}
+
+pub unsafe fn libevdev_kernel_set_led_value(
+    dev: *mut libevdev,
+    code: c_uint,
+    value: c_uint,
+) -> c_int {
+    let dev = &*dev;
+    let value = match value {
+        libevdev_led_value_LIBEVDEV_LED_ON => 1,
+        libevdev_led_value_LIBEVDEV_LED_OFF => 0,
+        _ => return -libc::EINVAL,
+    };
+
+    if !dev.has(EV_LED, code) {
+        return -libc::EINVAL;
+    }
+
+    write_events(
+        dev.fd,
+        &[
+            new_event(EV_LED, code, value),
+            new_event(EV_SYN, SYN_REPORT, 0),
+        ],
+    )
+}
+
+pub struct libevdev_uinput {
+    fd: c_int,
+    devnode: Option<CString>,
+}
+
+// Only the managed mode is supported, in which /dev/uinput is opened and closed for the caller.
+pub unsafe fn libevdev_uinput_create_from_device(
+    dev: *const libevdev,
+    uinput_fd: c_int,
+    uinput: *mut *mut libevdev_uinput,
+) -> c_int {
+    if uinput_fd != libevdev_uinput_open_mode_LIBEVDEV_UINPUT_OPEN_MANAGED {
+        return -libc::EINVAL;
+    }
+
+    let fd = libc::open(
+        b"/dev/uinput\0".as_ptr() as *const c_char,
+        libc::O_RDWR | libc::O_CLOEXEC,
+    );
+    if let Err(err) = check(fd) {
+        return err;
+    }
+
+    if let Err(err) = (*dev).setup_uinput(fd) {
+        libc::close(fd);
+        return err;
+    }
+
+    *uinput = Box::into_raw(Box::new(libevdev_uinput {
+        fd,
+        devnode: find_devnode(fd),
+    }));
+    0
+}
+
+// The device node is the event file in the sysfs directory of the device, which is named by uinput.
+unsafe fn find_devnode(fd: c_int) -> Option<CString> {
+    let mut sysname = [0 as c_char; 64];
+    check(libc::ioctl(
+        fd,
+        ui_get_sysname(sysname.len() - 1) as _,
+        sysname.as_mut_ptr(),
+    ))
+    .ok()?;
+
+    let sysname = CStr::from_ptr(sysname.as_ptr()).to_str().ok()?;
+    find_event_file(&Path::new("/sys/devices/virtual/input").join(sysname))
+}
+
+fn find_event_file(path: &Path) -> Option<CString> {
+    fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .find(|name| name.starts_with("event"))
+        .and_then(|name| CString::new(format!("/dev/input/{}", name)).ok())
+}
+
+pub unsafe fn libevdev_uinput_get_fd(uinput: *const libevdev_uinput) -> c_int {
+    (*uinput).fd
+}
+
+pub unsafe fn libevdev_uinput_get_devnode(uinput: *mut libevdev_uinput) -> *const c_char {
+    (*uinput)
+        .devnode
+        .as_ref()
+        .map(|devnode| devnode.as_ptr())
+        .unwrap_or(ptr::null())
+}
+
+pub unsafe fn libevdev_uinput_write_event(
+    uinput: *const libevdev_uinput,
+    r#type: c_uint,
+    code: c_uint,
+    value: c_int,
+) -> c_int {
+    write_events((*uinput).fd, &[new_event(r#type, code, value)])
+}
+
+pub unsafe fn libevdev_uinput_destroy(uinput: *mut libevdev_uinput) {
+    if uinput.is_null() {
+        return;
+    }
+
+    let uinput = Box::from_raw(uinput);
+    libc::ioctl(uinput.fd, UI_DEV_DESTROY as _);
+    libc::close(uinput.fd);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn device(codes: &[(u32, u32)]) -> libevdev {
+        let mut dev = libevdev::new();
+        for (r#type, code) in codes {
+            set_bit(&mut dev.types, *r#type);
+            set_bit(&mut dev.codes[*r#type as usize], *code);
+        }
+
+        dev
+    }
+
+    fn queued(dev: &mut libevdev) -> Vec<(u32, u32, i32)> {
+        dev.queue
+            .drain(..)
+            .map(|event| (event.type_ as u32, event.code as u32, event.value))
+            .collect()
+    }
+
+    fn absinfo(value: i32) -> input_absinfo {
+        input_absinfo {
+            value,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn state_changes_are_queued() {
+        let mut dev = device(&[(EV_KEY, KEY_A), (EV_KEY, KEY_B)]);
+        set_bit(&mut dev.state[EV_KEY as usize], KEY_A);
+
+        // KEY_C isn't supported by the device.
+        let mut state: CodeBits = [0; longs(KEY_MAX)];
+        set_bit(&mut state, KEY_B);
+        set_bit(&mut state, KEY_C);
+        dev.diff_state(EV_KEY, state);
+        assert_eq!(
+            queued(&mut dev),
+            vec![(EV_KEY, KEY_A, 0), (EV_KEY, KEY_B, 1)]
+        );
+
+        dev.diff_state(EV_KEY, state);
+        assert!(queued(&mut dev).is_empty());
+    }
+
+    #[test]
+    fn absolute_changes_are_queued() {
+        let mut dev = device(&[(EV_ABS, ABS_X), (EV_ABS, ABS_Y)]);
+
+        dev.diff_absinfo(ABS_X, absinfo(5));
+        dev.diff_absinfo(ABS_Y, absinfo(0));
+        assert_eq!(queued(&mut dev), vec![(EV_ABS, ABS_X, 5)]);
+
+        dev.diff_absinfo(ABS_X, absinfo(5));
+        assert!(queued(&mut dev).is_empty());
+    }
+
+    #[test]
+    fn slot_changes_select_the_slot() {
+        let mut dev = device(&[(EV_ABS, ABS_MT_SLOT), (EV_ABS, ABS_MT_POSITION_X)]);
+        dev.slots = vec![[0; MT_AXES]; 2];
+        dev.current_slot = 0;
+
+        let mut slots = vec![[0; MT_AXES]; 2];
+        slots[1][(ABS_MT_POSITION_X - ABS_MT_TOUCH_MAJOR) as usize] = 7;
+        dev.diff_slots(slots.clone(), absinfo(0));
+        assert_eq!(
+            queued(&mut dev),
+            vec![
+                (EV_ABS, ABS_MT_SLOT, 1),
+                (EV_ABS, ABS_MT_POSITION_X, 7),
+                (EV_ABS, ABS_MT_SLOT, 0),
+            ]
+        );
+
+        // Only the slot moved.
+        dev.diff_slots(slots, absinfo(1));
+        assert_eq!(queued(&mut dev), vec![(EV_ABS, ABS_MT_SLOT, 1)]);
+    }
+
+    #[test]
+    fn disabled_types_lose_their_codes() {
+        // FF_RUMBLE, the codes of force feedback aren't generated.
+        let mut dev = device(&[(EV_FF, 0x50), (EV_KEY, KEY_A)]);
+
+        unsafe {
+            assert_eq!(libevdev_disable_event_type(&mut dev, EV_FF), 0);
+            assert_eq!(libevdev_disable_event_type(&mut dev, EV_SYN), -1);
+        }
+
+        assert!(!dev.has(EV_FF, 0x50));
+        assert!(dev.has(EV_KEY, KEY_A));
+    }
+
+    // The values from the kernel headers, which only apply where _IOC has the generic layout.
+    #[test]
+    #[cfg(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64"
+    ))]
+    fn request_numbers_match_kernel() {
+        assert_eq!(EVIOCGID, 0x80084502);
+        assert_eq!(EVIOCGRAB, 0x40044590);
+        assert_eq!(eviocgname(256), 0x81004506);
+        assert_eq!(eviocgbit(EV_KEY, 96), 0x80604521);
+        assert_eq!(eviocgabs(ABS_X), 0x80184540);
+        assert_eq!(UI_DEV_CREATE, 0x5501);
+        assert_eq!(UI_DEV_SETUP, 0x405c5503);
+        assert_eq!(UI_ABS_SETUP, 0x401c5504);
+        assert_eq!(UI_SET_EVBIT, 0x40045564);
+        assert_eq!(ui_get_sysname(64), 0x8040552c);
+    }
+
+    #[test]
+    fn event_file_is_found() {
+        let dir = TempDir::new().unwrap();
+        for name in &["capabilities", "input5", "event12"] {
+            fs::create_dir(dir.path().join(name)).unwrap();
+        }
+
+        assert_eq!(
+            find_event_file(dir.path()),
+            Some(CString::new("/dev/input/event12").unwrap())
+        );
+        assert_eq!(find_event_file(&dir.path().join("missing")), None);
+    }
+}
@@ -1,12 +1,13 @@
+mod abs;
 mod button;
 mod key;
 
-use crate::event::{Axis, Button, Direction, Event, Key, KeyKind};
+use crate::event::{AbsAxis, Axis, Button, Direction, Event, Key, KeyKind};
 use crate::linux::glue::{self, input_event, timeval};
 
 impl Event {
-    pub(crate) fn to_raw(&self) -> input_event {
-        let (type_, code, value) = match *self {
+    pub(crate) fn to_raw(self) -> input_event {
+        let (type_, code, value) = match self {
             Event::MouseScroll { delta } => (glue::EV_REL as _, glue::REL_WHEEL as _, delta),
             Event::MouseMove {
                 axis: Axis::X,
@@ -24,6 +25,8 @@ impl Event {
                 direction: Direction::Down,
                 kind,
             } => (glue::EV_KEY as _, kind.to_raw(), 1),
+            Event::Absolute { axis, value } => (glue::EV_ABS as _, axis.to_raw(), value),
+            Event::Sync => (glue::EV_SYN as _, glue::SYN_REPORT as _, 0),
         };
 
         input_event {
@@ -56,6 +59,10 @@ impl Event {
                 direction: Direction::Down,
                 kind: KeyKind::from_raw(code as _)?,
             },
+            (glue::EV_ABS, code, value) => Event::Absolute {
+                axis: AbsAxis::from_raw(code as _)?,
+                value,
+            },
             _ => return None,
         };
 
@@ -65,15 +72,46 @@ impl Event {
 
 impl KeyKind {
     pub(crate) fn from_raw(code: u16) -> Option<KeyKind> {
-        Key::from_raw(code)
+        // KEY_RESERVED is never actually reported.
+        if code == 0 || code as u32 > glue::KEY_MAX {
+            return None;
+        }
+
+        let kind = Key::from_raw(code)
             .map(KeyKind::Key)
             .or_else(|| Button::from_raw(code).map(KeyKind::Button))
+            .unwrap_or(KeyKind::Raw(code));
+
+        Some(kind)
     }
 
-    pub(crate) fn to_raw(&self) -> u16 {
+    pub(crate) fn to_raw(self) -> u16 {
         match self {
             KeyKind::Key(key) => key.to_raw(),
             KeyKind::Button(button) => button.to_raw(),
+            KeyKind::Raw(code) => code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_codes_round_trip() {
+        for key in Key::ALL {
+            assert_eq!(Key::from_raw(key.to_raw()), Some(*key));
+        }
+
+        for button in Button::ALL {
+            assert_eq!(Button::from_raw(button.to_raw()), Some(*button));
+            assert_eq!(Key::from_raw(button.to_raw()), None);
+        }
+
+        for code in 1..=glue::KEY_MAX as u16 {
+            let kind = KeyKind::from_raw(code).unwrap();
+            assert_eq!(kind.to_raw(), code);
         }
     }
 }
@@ -0,0 +1,132 @@
+use crate::event::{AbsAxis, AbsInfo};
+use crate::linux::glue::{self, input_absinfo};
+
+impl AbsAxis {
+    pub(crate) fn to_raw(self) -> u16 {
+        use AbsAxis::*;
+
+        match self {
+            X => 0x0000,
+            Y => 0x0001,
+            Z => 0x0002,
+            Rx => 0x0003,
+            Ry => 0x0004,
+            Rz => 0x0005,
+            Throttle => 0x0006,
+            Rudder => 0x0007,
+            Wheel => 0x0008,
+            Gas => 0x0009,
+            Brake => 0x000A,
+            Hat0X => 0x0010,
+            Hat0Y => 0x0011,
+            Hat1X => 0x0012,
+            Hat1Y => 0x0013,
+            Hat2X => 0x0014,
+            Hat2Y => 0x0015,
+            Hat3X => 0x0016,
+            Hat3Y => 0x0017,
+            Pressure => 0x0018,
+            Distance => 0x0019,
+            TiltX => 0x001A,
+            TiltY => 0x001B,
+            ToolWidth => 0x001C,
+            Volume => 0x0020,
+            Profile => 0x0021,
+            Misc => 0x0028,
+            MtSlot => 0x002F,
+            MtTouchMajor => 0x0030,
+            MtTouchMinor => 0x0031,
+            MtWidthMajor => 0x0032,
+            MtWidthMinor => 0x0033,
+            MtOrientation => 0x0034,
+            MtPositionX => 0x0035,
+            MtPositionY => 0x0036,
+            MtToolType => 0x0037,
+            MtBlobId => 0x0038,
+            MtTrackingId => 0x0039,
+            MtPressure => 0x003A,
+            MtDistance => 0x003B,
+            MtToolX => 0x003C,
+            MtToolY => 0x003D,
+        }
+    }
+
+    pub(crate) fn from_raw(code: u16) -> Option<Self> {
+        use AbsAxis::*;
+
+        let axis = match code {
+            0x0000 => X,
+            0x0001 => Y,
+            0x0002 => Z,
+            0x0003 => Rx,
+            0x0004 => Ry,
+            0x0005 => Rz,
+            0x0006 => Throttle,
+            0x0007 => Rudder,
+            0x0008 => Wheel,
+            0x0009 => Gas,
+            0x000A => Brake,
+            0x0010 => Hat0X,
+            0x0011 => Hat0Y,
+            0x0012 => Hat1X,
+            0x0013 => Hat1Y,
+            0x0014 => Hat2X,
+            0x0015 => Hat2Y,
+            0x0016 => Hat3X,
+            0x0017 => Hat3Y,
+            0x0018 => Pressure,
+            0x0019 => Distance,
+            0x001A => TiltX,
+            0x001B => TiltY,
+            0x001C => ToolWidth,
+            0x0020 => Volume,
+            0x0021 => Profile,
+            0x0028 => Misc,
+            0x002F => MtSlot,
+            0x0030 => MtTouchMajor,
+            0x0031 => MtTouchMinor,
+            0x0032 => MtWidthMajor,
+            0x0033 => MtWidthMinor,
+            0x0034 => MtOrientation,
+            0x0035 => MtPositionX,
+            0x0036 => MtPositionY,
+            0x0037 => MtToolType,
+            0x0038 => MtBlobId,
+            0x0039 => MtTrackingId,
+            0x003A => MtPressure,
+            0x003B => MtDistance,
+            0x003C => MtToolX,
+            0x003D => MtToolY,
+            _ => return None,
+        };
+
+        Some(axis)
+    }
+
+    pub(crate) fn is_multitouch(&self) -> bool {
+        self.to_raw() >= glue::ABS_MT_SLOT as _
+    }
+}
+
+impl AbsInfo {
+    pub(crate) fn to_raw(self) -> input_absinfo {
+        input_absinfo {
+            value: self.minimum,
+            minimum: self.minimum,
+            maximum: self.maximum,
+            fuzz: self.fuzz,
+            flat: self.flat,
+            resolution: self.resolution,
+        }
+    }
+
+    pub(crate) fn from_raw(raw: &input_absinfo) -> Self {
+        Self {
+            minimum: raw.minimum,
+            maximum: raw.maximum,
+            fuzz: raw.fuzz,
+            flat: raw.flat,
+            resolution: raw.resolution,
+        }
+    }
+}
@@ -1,51 +1,113 @@
+use crate::device::{DeviceEvent, DeviceId, Led};
 use crate::event::Event;
 use crate::linux::event_reader::{EventReader, OpenError};
 use crate::linux::event_writer::EventWriter;
+use crate::linux::virtual_device::VirtualDevice;
+use futures::future;
 use futures::StreamExt;
-use inotify::{Inotify, WatchMask};
+use inotify::{EventMask, Inotify, WatchMask};
+use std::collections::HashMap;
 use std::io::{Error, ErrorKind};
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::fs;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::sync::oneshot::{self, Receiver};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tokio::time;
 
-const EVENT_PATH: &str = "/dev/input";
+pub(crate) const EVENT_PATH: &str = "/dev/input";
+
+// Devices that fail are reopened after this delay, which doubles with every failed attempt.
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+const RETRY_ATTEMPTS: u32 = 7;
+// Devices that fail after being open for this long start over with the first attempt.
+const STABLE: Duration = Duration::from_secs(30);
+
+struct Device {
+    // Local copy of the device we're reading from.
+    local: Arc<VirtualDevice>,
+    // LED changes to be applied to the physical device.
+    leds: UnboundedSender<(Led, bool)>,
+    // Whether events only reach us, otherwise the rest of the system has them already.
+    grabbed: Arc<AtomicBool>,
+}
+
+// What's going on with a device file.
+enum Entry {
+    // The device is being opened.
+    Opening,
+    // Opening the device failed and the task will try again later.
+    Waiting(JoinHandle<()>),
+    // The device is open and the task forwards its events.
+    Open(DeviceId, JoinHandle<()>),
+}
+
+struct Registry {
+    devices: HashMap<DeviceId, Device>,
+    paths: HashMap<PathBuf, Entry>,
+    passthrough: bool,
+    // Whether devices should be grabbed, only ever false in passthrough mode.
+    grab: watch::Receiver<bool>,
+}
+
+type Devices = Arc<Mutex<Registry>>;
 
 pub struct EventManager {
     writer: EventWriter,
-    event_receiver: UnboundedReceiver<Result<Event, Error>>,
+    event_receiver: UnboundedReceiver<DeviceEvent>,
     watcher_receiver: Receiver<Error>,
+    devices: Devices,
+    passthrough: bool,
+    grab: watch::Sender<bool>,
 }
 
 impl EventManager {
     pub async fn new() -> Result<Self, Error> {
-        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        Self::open(false).await
+    }
 
-        // HACK: When rkvm is run from the terminal, a race condition happens where the enter key
-        // release event is swallowed and the key will remain in a "pressed" state until the user manually presses it again.
-        // This is presumably due to the event being generated while we're in the process of grabbing
-        // the keyboard input device.
-        //
-        // This won't prevent this from happenning with other keys if they happen to be pressed at an
-        // unfortunate time, but that is unlikely to happen and will ease the life of people who run rkvm
-        // directly from the terminal for the time being until a proper fix is made.
-        time::sleep(Duration::from_millis(500)).await;
+    // Leaves the devices alone until set_grabbed is called, so that their events reach the rest of the system
+    // directly while they stay on this machine.
+    pub async fn passthrough() -> Result<Self, Error> {
+        Self::open(true).await
+    }
+
+    async fn open(passthrough: bool) -> Result<Self, Error> {
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (grab_sender, grab_receiver) = watch::channel(!passthrough);
 
+        // Devices are only grabbed once all their keys are released, so the enter key used to start rkvm from the
+        // terminal doesn't get stuck.
+        let devices = Arc::new(Mutex::new(Registry {
+            devices: HashMap::new(),
+            paths: HashMap::new(),
+            passthrough,
+            grab: grab_receiver,
+        }));
         let mut read_dir = fs::read_dir(EVENT_PATH).await?;
+        let mut paths = Vec::new();
         while let Some(entry) = read_dir.next_entry().await? {
-            spawn_reader(&entry.path(), event_sender.clone()).await?;
+            paths.push(entry.path());
         }
 
-        let writer = EventWriter::new().await?;
+        // Opening waits for keys to be released, so do it for all devices at once.
+        future::join_all(
+            paths
+                .iter()
+                .filter_map(|path| spawn_reader(path, &event_sender, &devices)),
+        )
+        .await;
 
-        // Sleep for a while to give userspace time to register our devices.
-        time::sleep(Duration::from_secs(1)).await;
+        let writer = EventWriter::new().await?;
 
         let (watcher_sender, watcher_receiver) = oneshot::channel();
+        let watcher_devices = devices.clone();
         tokio::spawn(async {
-            if let Err(err) = handle_notify(event_sender).await {
+            if let Err(err) = handle_notify(event_sender, watcher_devices).await {
                 let _ = watcher_sender.send(err);
             }
         });
@@ -54,10 +116,13 @@ impl EventManager {
             writer,
             event_receiver,
             watcher_receiver,
+            devices,
+            passthrough,
+            grab: grab_sender,
         })
     }
 
-    pub async fn read(&mut self) -> Result<Event, Error> {
+    pub async fn read(&mut self) -> Result<DeviceEvent, Error> {
         if let Ok(err) = self.watcher_receiver.try_recv() {
             return Err(err);
         }
@@ -65,45 +130,271 @@ impl EventManager {
         self.event_receiver
             .recv()
             .await
-            .ok_or_else(|| Error::new(ErrorKind::Other, "All devices closed"))?
+            .ok_or_else(|| Error::other("All devices closed"))
     }
 
     pub async fn write(&mut self, event: Event) -> Result<(), Error> {
         self.writer.write(event).await
     }
+
+    // Writes the event to the local copy of the device it was read from.
+    pub async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error> {
+        let device = self
+            .devices
+            .lock()
+            .unwrap()
+            .devices
+            .get(&id)
+            // Events of devices that aren't grabbed have reached the rest of the system already.
+            .filter(|device| device.grabbed.load(Ordering::Relaxed))
+            .map(|device| device.local.clone());
+        match device {
+            Some(device) => device.write(event),
+            // The device was disconnected in the meantime.
+            None => Ok(()),
+        }
+    }
+
+    // Sets a LED of the physical device.
+    pub async fn set_led(&mut self, id: DeviceId, led: Led, on: bool) -> Result<(), Error> {
+        if let Some(device) = self.devices.lock().unwrap().devices.get(&id) {
+            let _ = device.leds.send((led, on));
+        }
+
+        Ok(())
+    }
+
+    // Grabs the devices or lets them go, unless they're grabbed for good.
+    pub async fn set_grabbed(&mut self, grabbed: bool) -> Result<(), Error> {
+        if self.passthrough {
+            // The registry keeps a receiver around.
+            let _ = self.grab.send(grabbed);
+        }
+
+        Ok(())
+    }
 }
 
-async fn spawn_reader(
+// Starts opening the device file in its own task, unless it's open or being opened already. Opening can take a
+// while, so this way nobody waits for it.
+fn spawn_reader(
     path: &Path,
-    sender: UnboundedSender<Result<Event, Error>>,
-) -> Result<(), Error> {
-    if path.is_dir() {
-        return Ok(());
+    sender: &UnboundedSender<DeviceEvent>,
+    devices: &Devices,
+) -> Option<JoinHandle<()>> {
+    if !is_event_file(path) {
+        return None;
     }
 
-    // Skip non input event files.
-    if path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .map(|name| !name.starts_with("event"))
-        .unwrap_or(true)
-    {
-        return Ok(());
+    let mut registry = devices.lock().unwrap();
+    match registry.paths.get(path) {
+        Some(Entry::Opening) | Some(Entry::Open(_, _)) => return None,
+        // Something about the file changed, such as its permissions, so don't wait for the next attempt.
+        Some(Entry::Waiting(task)) => task.abort(),
+        None => {}
     }
 
-    let reader = match EventReader::open(&path).await {
+    registry.paths.insert(path.to_owned(), Entry::Opening);
+    Some(tokio::spawn(open(
+        path.to_owned(),
+        sender.clone(),
+        devices.clone(),
+        0,
+    )))
+}
+
+// Opens a device file with an Opening entry. A device that fails to open is reported and retried later, so that one
+// misbehaving device doesn't take the others down with it.
+async fn open(path: PathBuf, sender: UnboundedSender<DeviceEvent>, devices: Devices, attempt: u32) {
+    let passthrough = devices.lock().unwrap().passthrough;
+    let result = if passthrough {
+        EventReader::open_passthrough(&path).await
+    } else {
+        EventReader::open(&path).await
+    };
+
+    let mut registry = devices.lock().unwrap();
+    // The file was removed in the meantime.
+    if !matches!(registry.paths.get(&path), Some(Entry::Opening)) {
+        return;
+    }
+
+    let reader = match result {
         Ok(reader) => reader,
-        Err(OpenError::Io(err)) => return Err(err),
-        Err(OpenError::AlreadyOpened) => return Ok(()),
+        Err(OpenError::AlreadyOpened) => {
+            registry.paths.remove(&path);
+            return;
+        }
+        Err(OpenError::Io(err)) if err.kind() == ErrorKind::NotFound => {
+            registry.paths.remove(&path);
+            return;
+        }
+        Err(OpenError::Io(err)) => {
+            log::warn!("Failed to open {}: {}", path.display(), err);
+            schedule(
+                &mut registry,
+                path,
+                sender.clone(),
+                devices.clone(),
+                attempt,
+            );
+            return;
+        }
     };
 
-    tokio::spawn(handle_events(reader, sender));
-    Ok(())
+    let id = DeviceId::next();
+    let local = reader
+        .local()
+        .expect("Grabbed devices always have a local copy");
+    let (led_sender, led_receiver) = mpsc::unbounded_channel();
+    registry.devices.insert(
+        id,
+        Device {
+            local,
+            leds: led_sender,
+            grabbed: Arc::new(AtomicBool::new(reader.grabbed())),
+        },
+    );
+
+    // The task can't remove the device before it's been added, since that needs the lock we're holding.
+    let info = reader.info().clone();
+    let task = tokio::spawn(handle_device(
+        reader,
+        path.clone(),
+        id,
+        led_receiver,
+        sender.clone(),
+        devices.clone(),
+        attempt,
+    ));
+
+    registry.paths.insert(path, Entry::Open(id, task));
+    let _ = sender.send(DeviceEvent::Added { id, info });
 }
 
-async fn handle_notify(sender: UnboundedSender<Result<Event, Error>>) -> Result<(), Error> {
+// Tries to open the device file again later, until it works, runs out of attempts or goes away.
+fn schedule(
+    registry: &mut Registry,
+    path: PathBuf,
+    sender: UnboundedSender<DeviceEvent>,
+    devices: Devices,
+    attempt: u32,
+) {
+    if attempt >= RETRY_ATTEMPTS {
+        log::error!("Giving up on {}", path.display());
+        registry.paths.remove(&path);
+        return;
+    }
+
+    let task = tokio::spawn({
+        let path = path.clone();
+        async move {
+            time::sleep(RETRY_DELAY * 2u32.pow(attempt)).await;
+
+            {
+                let mut registry = devices.lock().unwrap();
+                if !matches!(registry.paths.get(&path), Some(Entry::Waiting(_))) {
+                    return;
+                }
+
+                registry.paths.insert(path.clone(), Entry::Opening);
+            }
+
+            open(path, sender, devices, attempt + 1).await;
+        }
+    });
+
+    registry.paths.insert(path, Entry::Waiting(task));
+}
+
+// Closes the device opened from the file, or stops trying to open it.
+fn forget(path: &Path, sender: &UnboundedSender<DeviceEvent>, devices: &Devices) {
+    let mut registry = devices.lock().unwrap();
+    match registry.paths.remove(path) {
+        Some(Entry::Open(id, task)) => {
+            task.abort();
+            registry.devices.remove(&id);
+            let _ = sender.send(DeviceEvent::Removed { id });
+        }
+        Some(Entry::Waiting(task)) => task.abort(),
+        // Opening notices that the entry is gone.
+        Some(Entry::Opening) | None => {}
+    }
+}
+
+async fn handle_device(
+    reader: EventReader,
+    path: PathBuf,
+    id: DeviceId,
+    leds: UnboundedReceiver<(Led, bool)>,
+    sender: UnboundedSender<DeviceEvent>,
+    devices: Devices,
+    attempt: u32,
+) {
+    let name = reader.info().name.clone();
+    let (grab, grabbed) = {
+        let registry = devices.lock().unwrap();
+        match registry.devices.get(&id) {
+            Some(device) => (registry.grab.clone(), device.grabbed.clone()),
+            // The file was removed before the task got to run.
+            None => return,
+        }
+    };
+
+    let opened = Instant::now();
+    let result = handle_events(reader, sender.clone(), id, leds, grab, grabbed).await;
+
+    let mut registry = devices.lock().unwrap();
+    // The file might have been removed at the same time, which takes care of the device.
+    if registry.devices.remove(&id).is_none() {
+        return;
+    }
+
+    registry.paths.remove(&path);
+    let _ = sender.send(DeviceEvent::Removed { id });
+
+    if let Err(err) = result {
+        log::error!("Device {} ({}) failed: {}", name, path.display(), err);
+
+        // Otherwise a device that fails right after opening would be reopened forever.
+        let attempt = if opened.elapsed() >= STABLE {
+            0
+        } else {
+            attempt
+        };
+        schedule(
+            &mut registry,
+            path,
+            sender.clone(),
+            devices.clone(),
+            attempt,
+        );
+    }
+}
+
+pub(crate) fn is_event_file(path: &Path) -> bool {
+    // Skip directories and non input event files.
+    !path.is_dir()
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with("event"))
+            .unwrap_or(false)
+}
+
+async fn handle_notify(
+    sender: UnboundedSender<DeviceEvent>,
+    devices: Devices,
+) -> Result<(), Error> {
     let mut inotify = Inotify::init()?;
-    inotify.add_watch(EVENT_PATH, WatchMask::CREATE)?;
+    inotify.add_watch(
+        EVENT_PATH,
+        WatchMask::CREATE
+            | WatchMask::ATTRIB
+            | WatchMask::DELETE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO,
+    )?;
 
     // This buffer size should be OK, since we don't expect a lot of devices
     // to be plugged in frequently.
@@ -111,30 +402,76 @@ async fn handle_notify(sender: UnboundedSender<Result<Event, Error>>) -> Result<
     while let Some(event) = stream.next().await {
         let event = event?;
 
-        if let Some(name) = event.name {
-            let path = Path::new(EVENT_PATH).join(&name);
-            spawn_reader(&path, sender.clone()).await?;
+        let path = match event.name {
+            Some(name) => Path::new(EVENT_PATH).join(&name),
+            None => continue,
+        };
+
+        if event
+            .mask
+            .intersects(EventMask::DELETE | EventMask::MOVED_FROM)
+        {
+            forget(&path, &sender, &devices);
+        } else {
+            // Device files are often only accessible by root when they're created, until udev sets them up, which
+            // shows up as a change of attributes.
+            spawn_reader(&path, &sender, &devices);
         }
     }
 
     Ok(())
 }
 
-async fn handle_events(mut reader: EventReader, sender: UnboundedSender<Result<Event, Error>>) {
+// Forwards events of the device until it's disconnected, or fails with an error.
+async fn handle_events(
+    mut reader: EventReader,
+    sender: UnboundedSender<DeviceEvent>,
+    id: DeviceId,
+    mut leds: UnboundedReceiver<(Led, bool)>,
+    mut grab: watch::Receiver<bool>,
+    grabbed: Arc<AtomicBool>,
+) -> Result<(), Error> {
+    let local = reader.local().unwrap();
     loop {
-        let result = match reader.read().await {
-            Ok(event) => sender.send(Ok(event)).is_ok(),
-            // This happens if the device is disconnected.
-            // In that case simply terminate the reading task.
-            Err(ref err) if err.raw_os_error() == Some(libc::ENODEV) => false,
-            Err(err) => {
-                let _ = sender.send(Err(err));
-                false
+        // Keys pressed before the device is grabbed have to be released for the rest of the system, so wait for
+        // that. Letting go is fine whenever, the system ignores releases of keys it never saw pressed.
+        let wanted = *grab.borrow();
+        if wanted != reader.grabbed() && !(wanted && reader.keys_down()) {
+            reader.set_grabbed(wanted)?;
+            grabbed.store(wanted, Ordering::Relaxed);
+        }
+
+        // Until then, the events still go to the rest of the system and not anywhere else.
+        let pending = wanted && !reader.grabbed();
+        let result = tokio::select! {
+            event = reader.read() => event.map(|event| Some(DeviceEvent::Event { id, event })),
+            led = local.read_led() => led.map(|(led, on)| Some(DeviceEvent::Led { id, led, on })),
+            Some((led, on)) = leds.recv() => {
+                // A LED that can't be set isn't worth losing the device over.
+                if let Err(err) = reader.set_led(led, on) {
+                    log::warn!("Failed to set {:?} of {}: {}", led, reader.info().name, err);
+                }
+
+                Ok(None)
             }
+            changed = grab.changed() => match changed {
+                Ok(()) => Ok(None),
+                // The manager is gone.
+                Err(_) => return Ok(()),
+            },
         };
 
-        if !result {
-            break;
+        match result {
+            Ok(Some(DeviceEvent::Event { .. })) if pending => {}
+            Ok(Some(event)) => {
+                if sender.send(event).is_err() {
+                    return Ok(());
+                }
+            }
+            Ok(None) => {}
+            // This happens if the device is disconnected.
+            Err(ref err) if err.raw_os_error() == Some(libc::ENODEV) => return Ok(()),
+            Err(err) => return Err(err),
         }
     }
 }
@@ -0,0 +1,73 @@
+use crate::device::{DeviceEvent, DeviceId};
+use crate::linux::event_manager::{self, EVENT_PATH};
+use crate::linux::event_reader::{EventReader, OpenError};
+use std::io::Error;
+use tokio::fs;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+// Reads events from all devices without grabbing them, so that they keep working as usual.
+pub struct EventMonitor {
+    receiver: UnboundedReceiver<DeviceEvent>,
+}
+
+impl EventMonitor {
+    pub async fn new() -> Result<Self, Error> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let mut read_dir = fs::read_dir(EVENT_PATH).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let path = entry.path();
+            if !event_manager::is_event_file(&path) {
+                continue;
+            }
+
+            // Some devices are only readable by root, which shouldn't keep the others from being watched.
+            let mut reader = match EventReader::open_ungrabbed(&path).await {
+                Ok(reader) => reader,
+                Err(OpenError::Io(err)) => {
+                    log::warn!("Failed to open {}: {}", path.display(), err);
+                    continue;
+                }
+                Err(OpenError::AlreadyOpened) => continue,
+            };
+
+            let id = DeviceId::next();
+            let info = reader.info().clone();
+            let _ = sender.send(DeviceEvent::Added { id, info });
+
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                loop {
+                    let result = match reader.read().await {
+                        Ok(event) => sender.send(DeviceEvent::Event { id, event }).is_ok(),
+                        Err(ref err) if err.raw_os_error() == Some(libc::ENODEV) => false,
+                        Err(err) => {
+                            log::warn!(
+                                "Device {} ({}) failed: {}",
+                                reader.info().name,
+                                path.display(),
+                                err
+                            );
+                            false
+                        }
+                    };
+
+                    if !result {
+                        break;
+                    }
+                }
+
+                let _ = sender.send(DeviceEvent::Removed { id });
+            });
+        }
+
+        Ok(Self { receiver })
+    }
+
+    pub async fn read(&mut self) -> Result<DeviceEvent, Error> {
+        self.receiver
+            .recv()
+            .await
+            .ok_or_else(|| Error::other("All devices closed"))
+    }
+}
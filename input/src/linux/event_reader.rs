@@ -1,31 +1,74 @@
-use crate::event::Event;
+use crate::device::{DeviceInfo, Led};
+use crate::event::{AbsAxis, Event};
 use crate::linux::device_id;
-use crate::linux::glue::{self, libevdev, libevdev_uinput};
+use crate::linux::glue::{self, input_event, libevdev};
+use crate::linux::virtual_device::VirtualDevice;
 use std::fs::{File, OpenOptions};
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 use std::mem::MaybeUninit;
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
+use std::os::unix::io::RawFd;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::io::unix::AsyncFd;
 
+// How long to wait at most for keys to be released before grabbing a device anyway.
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub(crate) struct EventReader {
     file: AsyncFd<File>,
     evdev: *mut libevdev,
-    uinput: *mut libevdev_uinput,
+    // Virtual copy of the device, which gets all events that are not forwarded anywhere.
+    // Only present if the device isn't just watched.
+    local: Option<Arc<VirtualDevice>>,
+    // Whether events only reach us, rather than the rest of the system too.
+    grabbed: bool,
+    info: DeviceInfo,
+    multitouch: bool,
+    // Whether the current slot was already sent in the current frame.
+    slot_sent: bool,
+    pending: Option<Event>,
+    // Whether events were dropped and the state of the device is being synchronized.
+    syncing: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Grabbed,
+    // Like Grabbed, but the device is only grabbed once asked to.
+    Passthrough,
+    Monitor,
 }
 
 impl EventReader {
     pub async fn open(path: &Path) -> Result<Self, OpenError> {
+        Self::open_mode(path, Mode::Grabbed).await
+    }
+
+    // Opens the device with a local copy, but leaves grabbing it to set_grabbed.
+    pub async fn open_passthrough(path: &Path) -> Result<Self, OpenError> {
+        Self::open_mode(path, Mode::Passthrough).await
+    }
+
+    // Opens the device just to watch it, events still reach the rest of the system.
+    pub async fn open_ungrabbed(path: &Path) -> Result<Self, OpenError> {
+        Self::open_mode(path, Mode::Monitor).await
+    }
+
+    async fn open_mode(path: &Path, mode: Mode) -> Result<Self, OpenError> {
         let path = path.to_owned();
-        tokio::task::spawn_blocking(move || Self::open_sync(&path))
+        tokio::task::spawn_blocking(move || Self::open_sync(&path, mode))
             .await
             .map_err(|err| OpenError::Io(err.into()))?
     }
 
-    fn open_sync(path: &Path) -> Result<Self, OpenError> {
+    fn open_sync(path: &Path, mode: Mode) -> Result<Self, OpenError> {
+        // LEDs are set by writing to the device.
         let file = OpenOptions::new()
             .read(true)
+            .write(mode != Mode::Monitor)
             .custom_flags(libc::O_NONBLOCK)
             .open(path)
             .and_then(AsyncFd::new)?;
@@ -37,19 +80,10 @@ impl EventReader {
         }
 
         let evdev = unsafe { evdev.assume_init() };
-        let (vendor, product, version) = unsafe {
-            (
-                glue::libevdev_get_id_vendor(evdev),
-                glue::libevdev_get_id_product(evdev),
-                glue::libevdev_get_id_version(evdev),
-            )
-        };
+        let info = unsafe { DeviceInfo::from_evdev(evdev) };
 
         // Check if we're not opening our own virtual device.
-        if vendor == device_id::VENDOR as _
-            && product == device_id::PRODUCT as _
-            && version == device_id::VERSION as _
-        {
+        if info.is_own() {
             unsafe {
                 glue::libevdev_free(evdev);
             }
@@ -57,85 +91,278 @@ impl EventReader {
             return Err(OpenError::AlreadyOpened);
         }
 
+        let multitouch =
+            unsafe { glue::libevdev_has_event_code(evdev, glue::EV_ABS, glue::ABS_MT_SLOT) } == 1;
+        if mode == Mode::Monitor {
+            return Ok(Self {
+                file,
+                evdev,
+                local: None,
+                grabbed: false,
+                info,
+                multitouch,
+                slot_sent: false,
+                pending: None,
+                syncing: false,
+            });
+        }
+
+        // The local copy keeps the identity of the device, so that userspace applies the same quirks to it. Force
+        // feedback is left out though, since nothing would service the effects uploaded to it.
         unsafe {
-            glue::libevdev_set_id_vendor(evdev, device_id::VENDOR as _);
-            glue::libevdev_set_id_product(evdev, device_id::PRODUCT as _);
-            glue::libevdev_set_id_version(evdev, device_id::VERSION as _);
+            device_id::set_phys(evdev);
+            glue::libevdev_disable_event_type(evdev, glue::EV_FF);
         }
 
-        let ret = unsafe { glue::libevdev_grab(evdev, glue::libevdev_grab_mode_LIBEVDEV_GRAB) };
-        if ret < 0 {
-            unsafe {
-                glue::libevdev_free(evdev);
-            }
+        if mode == Mode::Grabbed {
+            // Grabbing the device while a key is down would leave the key pressed for the rest of the system, since
+            // the release would only reach us.
+            let result = wait_for_release(file.as_raw_fd(), evdev)
+                .and_then(|_| grab(evdev, glue::libevdev_grab_mode_LIBEVDEV_GRAB));
+            if let Err(err) = result {
+                unsafe {
+                    glue::libevdev_free(evdev);
+                }
 
-            return Err(Error::from_raw_os_error(-ret).into());
+                return Err(err.into());
+            }
         }
 
-        let mut uinput = MaybeUninit::uninit();
-        let ret = unsafe {
-            glue::libevdev_uinput_create_from_device(
-                evdev,
-                glue::libevdev_uinput_open_mode_LIBEVDEV_UINPUT_OPEN_MANAGED,
-                uinput.as_mut_ptr(),
-            )
+        let local = match unsafe { VirtualDevice::from_evdev(evdev) } {
+            Ok(local) => local,
+            Err(err) => {
+                unsafe { glue::libevdev_free(evdev) };
+                return Err(err.into());
+            }
         };
 
-        if ret < 0 {
-            unsafe { glue::libevdev_free(evdev) };
-            return Err(Error::from_raw_os_error(-ret).into());
-        }
-
-        let uinput = unsafe { uinput.assume_init() };
         Ok(Self {
             file,
             evdev,
-            uinput,
+            local: Some(Arc::new(local)),
+            grabbed: mode == Mode::Grabbed,
+            info,
+            multitouch,
+            slot_sent: false,
+            pending: None,
+            syncing: false,
         })
     }
 
+    pub fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    pub fn local(&self) -> Option<Arc<VirtualDevice>> {
+        self.local.clone()
+    }
+
+    pub fn grabbed(&self) -> bool {
+        self.grabbed
+    }
+
+    pub fn set_grabbed(&mut self, grabbed: bool) -> Result<(), Error> {
+        if grabbed == self.grabbed {
+            return Ok(());
+        }
+
+        let mode = if grabbed {
+            glue::libevdev_grab_mode_LIBEVDEV_GRAB
+        } else {
+            glue::libevdev_grab_mode_LIBEVDEV_UNGRAB
+        };
+
+        grab(self.evdev, mode)?;
+        self.grabbed = grabbed;
+
+        Ok(())
+    }
+
+    // Whether any key of the device is held down, as far as the events read so far tell.
+    pub fn keys_down(&self) -> bool {
+        keys_down(self.evdev)
+    }
+
+    pub fn set_led(&mut self, led: Led, on: bool) -> Result<(), Error> {
+        // The LED state of the other side might include LEDs this device doesn't have.
+        if !self.info.leds.contains(&led) {
+            return Ok(());
+        }
+
+        let value = if on {
+            glue::libevdev_led_value_LIBEVDEV_LED_ON
+        } else {
+            glue::libevdev_led_value_LIBEVDEV_LED_OFF
+        };
+
+        let ret = unsafe { glue::libevdev_kernel_set_led_value(self.evdev, led.to_raw(), value) };
+        if ret < 0 {
+            return Err(Error::from_raw_os_error(-ret));
+        }
+
+        Ok(())
+    }
+
     pub async fn read(&mut self) -> Result<Event, Error> {
+        if let Some(event) = self.pending.take() {
+            return Ok(event);
+        }
+
         loop {
-            let result = self.file.readable().await?.try_io(|_| {
-                let mut event = MaybeUninit::uninit();
-                let ret = unsafe {
-                    glue::libevdev_next_event(
+            let (status, event) = if self.syncing {
+                match next_event(self.evdev, glue::libevdev_read_flag_LIBEVDEV_READ_FLAG_SYNC) {
+                    Ok(result) => result,
+                    // Everything that changed while events were dropped has been reported.
+                    Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                        self.syncing = false;
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                }
+            } else {
+                let result = self.file.readable().await?.try_io(|_| {
+                    next_event(
                         self.evdev,
                         glue::libevdev_read_flag_LIBEVDEV_READ_FLAG_NORMAL,
-                        event.as_mut_ptr(),
                     )
-                };
+                });
 
-                if ret < 0 {
-                    return Err(Error::from_raw_os_error(-ret));
+                match result {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(err)) => return Err(err),
+                    Err(_) => continue, // This means it would block.
                 }
+            };
 
-                let event = unsafe { event.assume_init() };
-                Ok(event)
-            });
+            // The kernel ran out of buffer space and dropped events, which would leave keys stuck.
+            // libevdev compares the state of the device with what it knew before and hands out the differences
+            // as events, which are then processed like any other.
+            if !self.syncing && status == glue::libevdev_read_status_LIBEVDEV_READ_STATUS_SYNC as _
+            {
+                self.syncing = true;
+                continue;
+            }
 
-            let event = match result {
-                Ok(Ok(event)) => event,
-                Ok(Err(err)) => return Err(err),
-                Err(_) => continue, // This means it would block.
+            let translated = match (event.type_ as _, event.code as _) {
+                // Absolute events and pointer movement only make sense in groups, so let the other side know where a
+                // group ends. Our own device gets the report when the Sync is written to it, after the events of the
+                // group.
+                (glue::EV_SYN, glue::SYN_REPORT)
+                    if !self.info.absolute.is_empty() || !self.info.relative.is_empty() =>
+                {
+                    self.slot_sent = false;
+                    return Ok(Event::Sync);
+                }
+                _ => Event::from_raw(event),
             };
 
-            if let Some(event) = Event::from_raw(event) {
+            match translated {
+                Some(Event::Absolute {
+                    axis: AbsAxis::MtSlot,
+                    ..
+                }) => {
+                    self.slot_sent = true;
+                }
+                // The kernel only reports the slot when it changes, which means that the other side might not know
+                // which slot the following events apply to, so tell it.
+                Some(event @ Event::Absolute { axis, .. })
+                    if self.multitouch && !self.slot_sent && axis.is_multitouch() =>
+                {
+                    let slot = Event::Absolute {
+                        axis: AbsAxis::MtSlot,
+                        value: unsafe { glue::libevdev_get_current_slot(self.evdev) },
+                    };
+
+                    self.slot_sent = true;
+                    self.pending = Some(event);
+                    return Ok(slot);
+                }
+                _ => {}
+            }
+
+            if let Some(event) = translated {
                 return Ok(event);
             }
 
             // Not understood, write it back.
-            let ret = unsafe {
-                glue::libevdev_uinput_write_event(
-                    self.uinput as *const _,
-                    event.type_ as _,
-                    event.code as _,
-                    event.value,
-                )
-            };
+            self.write_back(event)?;
+        }
+    }
 
-            if ret < 0 {
-                return Err(Error::from_raw_os_error(-ret));
+    fn write_back(&self, event: input_event) -> Result<(), Error> {
+        match &self.local {
+            Some(local) if self.grabbed => local.write_raw(event),
+            // The event has reached the rest of the system already.
+            _ => Ok(()),
+        }
+    }
+}
+
+fn grab(evdev: *mut libevdev, mode: u32) -> Result<(), Error> {
+    let ret = unsafe { glue::libevdev_grab(evdev, mode) };
+    if ret < 0 {
+        return Err(Error::from_raw_os_error(-ret));
+    }
+
+    Ok(())
+}
+
+fn keys_down(evdev: *mut libevdev) -> bool {
+    (0..=glue::KEY_MAX)
+        .any(|code| unsafe { glue::libevdev_get_event_value(evdev, glue::EV_KEY, code) } != 0)
+}
+
+fn next_event(evdev: *mut libevdev, flags: u32) -> Result<(i32, input_event), Error> {
+    let mut event = MaybeUninit::uninit();
+    let ret = unsafe { glue::libevdev_next_event(evdev, flags as _, event.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(Error::from_raw_os_error(-ret));
+    }
+
+    let event = unsafe { event.assume_init() };
+    Ok((ret, event))
+}
+
+fn wait_for_release(fd: RawFd, evdev: *mut libevdev) -> Result<(), Error> {
+    // Keys which are held down for long, such as broken ones, shouldn't keep the device from being used.
+    let deadline = Instant::now() + RELEASE_TIMEOUT;
+    loop {
+        let down = keys_down(evdev);
+        let now = Instant::now();
+        if !down || now >= deadline {
+            return Ok(());
+        }
+
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let timeout = (deadline - now).as_millis() as _;
+        if unsafe { libc::poll(&mut pollfd, 1, timeout) } < 0 {
+            let err = Error::last_os_error();
+            if err.kind() == ErrorKind::Interrupted {
+                continue;
+            }
+
+            return Err(err);
+        }
+
+        // The events have already reached the rest of the system, they're only read to let libevdev keep track of
+        // which keys are down.
+        loop {
+            match next_event(evdev, glue::libevdev_read_flag_LIBEVDEV_READ_FLAG_NORMAL) {
+                Ok((status, _))
+                    if status == glue::libevdev_read_status_LIBEVDEV_READ_STATUS_SYNC as _ =>
+                {
+                    while next_event(evdev, glue::libevdev_read_flag_LIBEVDEV_READ_FLAG_SYNC)
+                        .is_ok()
+                    {}
+                }
+                Ok(_) => {}
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
             }
         }
     }
@@ -144,7 +371,6 @@ impl EventReader {
 impl Drop for EventReader {
     fn drop(&mut self) {
         unsafe {
-            glue::libevdev_uinput_destroy(self.uinput);
             glue::libevdev_free(self.evdev);
         }
     }
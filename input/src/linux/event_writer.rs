@@ -1,13 +1,34 @@
+use crate::device::{DeviceId, DeviceInfo, Led};
 use crate::event::Event;
 use crate::linux::device_id;
-use crate::linux::glue::{self, input_event, libevdev, libevdev_uinput};
-use std::io::{Error, ErrorKind};
-use std::mem::MaybeUninit;
+use crate::linux::glue::{self, libevdev};
+use crate::linux::virtual_device::VirtualDevice;
+use std::collections::HashMap;
+use std::io::Error;
 use std::ops::RangeInclusive;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
 
 pub struct EventWriter {
-    evdev: *mut libevdev,
-    uinput: *mut libevdev_uinput,
+    // Gets events that don't belong to any particular device.
+    device: VirtualDevice,
+    // Mirrors of devices on the other side, so that userspace treats each of them like the original.
+    devices: HashMap<DeviceId, Mirror>,
+    led_sender: UnboundedSender<Result<(DeviceId, Led, bool), Error>>,
+    led_receiver: UnboundedReceiver<Result<(DeviceId, Led, bool), Error>>,
+}
+
+struct Mirror {
+    device: Arc<VirtualDevice>,
+    // Reports LED changes made by userspace.
+    task: JoinHandle<()>,
+}
+
+impl Drop for Mirror {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
 }
 
 impl EventWriter {
@@ -16,80 +37,74 @@ impl EventWriter {
     }
 
     fn new_sync() -> Result<Self, Error> {
-        let evdev = unsafe { glue::libevdev_new() };
-        if evdev.is_null() {
-            return Err(Error::new(ErrorKind::Other, "Failed to create device"));
-        }
-
-        if let Err(err) = unsafe { setup_evdev(evdev) } {
-            unsafe {
-                glue::libevdev_free(evdev);
-            }
+        let device = VirtualDevice::new(|evdev| unsafe { setup_evdev(evdev) })?;
+        let (led_sender, led_receiver) = mpsc::unbounded_channel();
+        Ok(Self {
+            device,
+            devices: HashMap::new(),
+            led_sender,
+            led_receiver,
+        })
+    }
 
-            return Err(err);
+    pub async fn write(&mut self, event: Event) -> Result<(), Error> {
+        // The generic device has no absolute axes, the kernel would drop the event without telling anyone.
+        if let Event::Absolute { .. } = event {
+            log::error!(
+                "Dropping {:?} without a device, absolute events need one",
+                event
+            );
+            return Ok(());
         }
 
-        let mut uinput = MaybeUninit::uninit();
-        let ret = unsafe {
-            glue::libevdev_uinput_create_from_device(
-                evdev,
-                glue::libevdev_uinput_open_mode_LIBEVDEV_UINPUT_OPEN_MANAGED,
-                uinput.as_mut_ptr(),
-            )
-        };
+        self.device.write(event)
+    }
 
-        if ret < 0 {
-            unsafe { glue::libevdev_free(evdev) };
-            return Err(Error::from_raw_os_error(-ret));
-        }
+    pub async fn add_device(&mut self, id: DeviceId, info: DeviceInfo) -> Result<(), Error> {
+        let device = tokio::task::spawn_blocking(move || {
+            VirtualDevice::new(|evdev| unsafe { info.setup_evdev(evdev) })
+        })
+        .await??;
+
+        let device = Arc::new(device);
+        let task = tokio::spawn({
+            let device = device.clone();
+            let sender = self.led_sender.clone();
+            async move {
+                loop {
+                    let result = device.read_led().await.map(|(led, on)| (id, led, on));
+                    let failed = result.is_err();
+                    if sender.send(result).is_err() || failed {
+                        return;
+                    }
+                }
+            }
+        });
 
-        let uinput = unsafe { uinput.assume_init() };
-        Ok(Self { evdev, uinput })
+        self.devices.insert(id, Mirror { device, task });
+        Ok(())
     }
 
-    pub async fn write(&mut self, event: Event) -> Result<(), Error> {
-        self.write_raw(event.to_raw())
+    pub async fn remove_device(&mut self, id: DeviceId) -> Result<(), Error> {
+        self.devices.remove(&id);
+        Ok(())
     }
 
-    pub(crate) fn write_raw(&mut self, event: input_event) -> Result<(), Error> {
-        // As far as tokio is concerned, the FD never becomes ready for writing, so just write it normally.
-        // If an error happens, it will be propagated to caller and the FD is opened in nonblocking mode anyway,
-        // so it shouldn't be an issue.
-        let events = [
-            (event.type_, event.code, event.value),
-            (glue::EV_SYN as _, glue::SYN_REPORT as _, 0), // Include EV_SYN.
-        ];
-
-        for (r#type, code, value) in events.iter().cloned() {
-            let ret = unsafe {
-                glue::libevdev_uinput_write_event(
-                    self.uinput as *const _,
-                    r#type as _,
-                    code as _,
-                    value,
-                )
-            };
-
-            if ret < 0 {
-                return Err(Error::from_raw_os_error(-ret));
-            }
+    pub async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error> {
+        match self.devices.get(&id) {
+            Some(mirror) => mirror.device.write(event),
+            // The device might have been removed in the meantime, there's nowhere to write the event to.
+            None => Ok(()),
         }
-
-        Ok(())
     }
-}
 
-impl Drop for EventWriter {
-    fn drop(&mut self) {
-        unsafe {
-            glue::libevdev_uinput_destroy(self.uinput);
-            glue::libevdev_free(self.evdev);
-        }
+    // Waits until userspace changes a LED of one of the mirrored devices.
+    pub async fn read_led(&mut self) -> Result<(DeviceId, Led, bool), Error> {
+        // We're holding a sender ourselves, so the channel can never be closed.
+        self.led_receiver.recv().await.unwrap()
     }
 }
 
-unsafe impl Send for EventWriter {}
-
 const TYPES: &[(u32, &[RangeInclusive<u32>])] = &[
     (glue::EV_SYN, &[glue::SYN_REPORT..=glue::SYN_REPORT]),
     (glue::EV_REL, &[0..=glue::REL_MAX]),
@@ -98,6 +113,7 @@ const TYPES: &[(u32, &[RangeInclusive<u32>])] = &[
 
 unsafe fn setup_evdev(evdev: *mut libevdev) -> Result<(), Error> {
     glue::libevdev_set_name(evdev, b"rkvm\0".as_ptr() as *const _);
+    device_id::set_phys(evdev);
     glue::libevdev_set_id_vendor(evdev, device_id::VENDOR as _);
     glue::libevdev_set_id_product(evdev, device_id::PRODUCT as _);
     glue::libevdev_set_id_version(evdev, device_id::VERSION as _);
@@ -1,3 +1,13 @@
-#![allow(warnings)]
+// Bindings to libevdev, or its pure Rust stand-in when built with the ioctl feature.
 
-include!(concat!(env!("OUT_DIR"), "/glue.rs"));
+#[cfg(not(feature = "ioctl"))]
+#[allow(warnings)]
+mod bindings {
+    include!(concat!(env!("OUT_DIR"), "/glue.rs"));
+}
+
+#[cfg(not(feature = "ioctl"))]
+pub use bindings::*;
+
+#[cfg(feature = "ioctl")]
+pub use crate::linux::evdev::*;
@@ -0,0 +1,203 @@
+use crate::device::Led;
+use crate::event::Event;
+use crate::linux::glue::{self, input_event, libevdev, libevdev_uinput};
+use std::ffi::{CStr, OsStr};
+use std::fs;
+use std::io::Error;
+use std::mem::{self, MaybeUninit};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::RawFd;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::io::unix::AsyncFd;
+
+// How long to wait at most for userspace to set up a new device.
+const REGISTER_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub(crate) struct VirtualDevice {
+    uinput: *mut libevdev_uinput,
+    // The uinput file, which is where userspace requests (such as LED changes) can be read from.
+    file: AsyncFd<RawFd>,
+    // Events of devices with absolute axes come in groups terminated by Event::Sync,
+    // other events are synced one by one.
+    absolute: bool,
+}
+
+impl VirtualDevice {
+    pub fn new(setup: impl FnOnce(*mut libevdev) -> Result<(), Error>) -> Result<Self, Error> {
+        let evdev = unsafe { glue::libevdev_new() };
+        if evdev.is_null() {
+            return Err(Error::other("Failed to create device"));
+        }
+
+        let result = setup(evdev).and_then(|_| unsafe { Self::from_evdev(evdev) });
+        unsafe {
+            glue::libevdev_free(evdev);
+        }
+
+        result
+    }
+
+    // Creates a virtual device with the same properties as the provided one.
+    pub unsafe fn from_evdev(evdev: *mut libevdev) -> Result<Self, Error> {
+        let mut uinput = MaybeUninit::uninit();
+        let ret = glue::libevdev_uinput_create_from_device(
+            evdev,
+            glue::libevdev_uinput_open_mode_LIBEVDEV_UINPUT_OPEN_MANAGED,
+            uinput.as_mut_ptr(),
+        );
+
+        if ret < 0 {
+            return Err(Error::from_raw_os_error(-ret));
+        }
+
+        let uinput = uinput.assume_init();
+        let fd = glue::libevdev_uinput_get_fd(uinput as *const _);
+        let file = match set_nonblocking(fd).and_then(|_| AsyncFd::new(fd)) {
+            Ok(file) => file,
+            Err(err) => {
+                glue::libevdev_uinput_destroy(uinput);
+                return Err(err);
+            }
+        };
+
+        // Events written before userspace starts listening to the device would be lost.
+        let devnode = glue::libevdev_uinput_get_devnode(uinput);
+        if !devnode.is_null() {
+            wait_registered(Path::new(OsStr::from_bytes(
+                CStr::from_ptr(devnode).to_bytes(),
+            )));
+        }
+
+        Ok(Self {
+            uinput,
+            file,
+            absolute: glue::libevdev_has_event_type(evdev, glue::EV_ABS) == 1,
+        })
+    }
+
+    pub fn write(&self, event: Event) -> Result<(), Error> {
+        let raw = event.to_raw();
+        if let Event::Sync = event {
+            // Other events have been synced already.
+            if !self.absolute {
+                return Ok(());
+            }
+
+            return self.write_raw(raw);
+        }
+
+        self.write_raw(raw)?;
+        if !self.absolute {
+            self.write_raw(Event::Sync.to_raw())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_raw(&self, event: input_event) -> Result<(), Error> {
+        // As far as tokio is concerned, the FD never becomes ready for writing, so just write it normally.
+        // If an error happens, it will be propagated to caller and the FD is opened in nonblocking mode anyway,
+        // so it shouldn't be an issue.
+        let ret = unsafe {
+            glue::libevdev_uinput_write_event(
+                self.uinput as *const _,
+                event.type_ as _,
+                event.code as _,
+                event.value,
+            )
+        };
+
+        if ret < 0 {
+            return Err(Error::from_raw_os_error(-ret));
+        }
+
+        Ok(())
+    }
+
+    // Waits until userspace changes a LED of the device.
+    pub async fn read_led(&self) -> Result<(Led, bool), Error> {
+        loop {
+            let result = self.file.readable().await?.try_io(|fd| {
+                let mut event = MaybeUninit::<input_event>::uninit();
+                let ret = unsafe {
+                    libc::read(
+                        *fd.get_ref(),
+                        event.as_mut_ptr() as *mut _,
+                        mem::size_of::<input_event>(),
+                    )
+                };
+
+                if ret < 0 {
+                    return Err(Error::last_os_error());
+                }
+
+                let event = unsafe { event.assume_init() };
+                Ok(event)
+            });
+
+            let event = match result {
+                Ok(Ok(event)) => event,
+                Ok(Err(err)) => return Err(err),
+                Err(_) => continue, // This means it would block.
+            };
+
+            // Other requests, such as force feedback uploads, are not supported.
+            if event.type_ as u32 != glue::EV_LED {
+                continue;
+            }
+
+            if let Some(led) = Led::from_raw(event.code as _) {
+                return Ok((led, event.value != 0));
+            }
+        }
+    }
+}
+
+// Waits until udev is done with the device, which is when programs such as the display server learn about it.
+fn wait_registered(devnode: &Path) {
+    // Without udev, there's nothing to wait for.
+    if !Path::new("/run/udev/control").exists() {
+        return;
+    }
+
+    let deadline = Instant::now() + REGISTER_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Ok(metadata) = fs::metadata(devnode) {
+            let rdev = metadata.rdev();
+            let major = ((rdev >> 8) & 0xfff) | ((rdev >> 32) & !0xfff);
+            let minor = (rdev & 0xff) | ((rdev >> 12) & !0xff);
+
+            // udev writes its database entry once it has finished processing the device.
+            if Path::new(&format!("/run/udev/data/c{}:{}", major, minor)).exists() {
+                return;
+            }
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn set_nonblocking(fd: RawFd) -> Result<(), Error> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+impl Drop for VirtualDevice {
+    fn drop(&mut self) {
+        unsafe {
+            glue::libevdev_uinput_destroy(self.uinput);
+        }
+    }
+}
+
+// The uinput handle isn't changed after creation. Events are written and LED requests read with single syscalls on
+// the file descriptor, which the kernel allows from multiple threads at once.
+unsafe impl Send for VirtualDevice {}
+unsafe impl Sync for VirtualDevice {}
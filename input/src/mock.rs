@@ -0,0 +1,232 @@
+// In-memory backend for driving the server and client logic from tests, without root or /dev/uinput.
+// Events are fed in through the handles, everything the code under test writes comes out of them.
+
+use crate::backend::{EventSink, EventSource};
+use crate::device::{DeviceEvent, DeviceId, DeviceInfo, Led};
+use crate::event::Event;
+use async_trait::async_trait;
+use futures::FutureExt;
+use std::io::{Error, ErrorKind};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+// Something written to a mock backend.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Output {
+    // Events without a device went to the generic device.
+    Event {
+        device: Option<DeviceId>,
+        event: Event,
+    },
+    DeviceAdded {
+        id: DeviceId,
+        info: DeviceInfo,
+    },
+    DeviceRemoved {
+        id: DeviceId,
+    },
+    Led {
+        device: DeviceId,
+        led: Led,
+        on: bool,
+    },
+    Grabbed {
+        grabbed: bool,
+    },
+}
+
+// Allocates an ID as if a device was connected on the other side.
+pub fn device_id() -> DeviceId {
+    DeviceId::next()
+}
+
+pub fn source() -> (MockSource, SourceHandle) {
+    let (event_sender, event_receiver) = mpsc::unbounded_channel();
+    let (output_sender, output_receiver) = mpsc::unbounded_channel();
+
+    let source = MockSource {
+        events: event_receiver,
+        output: output_sender,
+    };
+    let handle = SourceHandle {
+        events: event_sender,
+        output: output_receiver,
+    };
+
+    (source, handle)
+}
+
+pub fn sink() -> (MockSink, SinkHandle) {
+    let (led_sender, led_receiver) = mpsc::unbounded_channel();
+    let (output_sender, output_receiver) = mpsc::unbounded_channel();
+
+    let sink = MockSink {
+        leds: led_receiver,
+        output: output_sender,
+    };
+    let handle = SinkHandle {
+        leds: led_sender,
+        output: output_receiver,
+    };
+
+    (sink, handle)
+}
+
+pub struct MockSource {
+    events: UnboundedReceiver<DeviceEvent>,
+    output: UnboundedSender<Output>,
+}
+
+impl MockSource {
+    fn output(&self, output: Output) -> Result<(), Error> {
+        // Nobody is looking at the output anymore, which is fine.
+        let _ = self.output.send(output);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSource for MockSource {
+    async fn read(&mut self) -> Result<DeviceEvent, Error> {
+        self.events
+            .recv()
+            .await
+            .ok_or_else(|| Error::new(ErrorKind::BrokenPipe, "Mock source closed"))
+    }
+
+    async fn write(&mut self, event: Event) -> Result<(), Error> {
+        self.output(Output::Event {
+            device: None,
+            event,
+        })
+    }
+
+    async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error> {
+        self.output(Output::Event {
+            device: Some(id),
+            event,
+        })
+    }
+
+    async fn set_led(&mut self, id: DeviceId, led: Led, on: bool) -> Result<(), Error> {
+        self.output(Output::Led {
+            device: id,
+            led,
+            on,
+        })
+    }
+
+    async fn set_grabbed(&mut self, grabbed: bool) -> Result<(), Error> {
+        self.output(Output::Grabbed { grabbed })
+    }
+}
+
+// Plays the part of the physical devices and of the local OS.
+pub struct SourceHandle {
+    events: UnboundedSender<DeviceEvent>,
+    output: UnboundedReceiver<Output>,
+}
+
+impl SourceHandle {
+    pub fn add_device(&self, info: DeviceInfo) -> DeviceId {
+        let id = device_id();
+        self.send(DeviceEvent::Added { id, info });
+
+        id
+    }
+
+    pub fn remove_device(&self, id: DeviceId) {
+        self.send(DeviceEvent::Removed { id });
+    }
+
+    pub fn event(&self, id: DeviceId, event: Event) {
+        self.send(DeviceEvent::Event { id, event });
+    }
+
+    // Changes a LED as if the local OS did it.
+    pub fn set_led(&self, id: DeviceId, led: Led, on: bool) {
+        self.send(DeviceEvent::Led { id, led, on });
+    }
+
+    // Returns what was written to the source next, None if it has been dropped.
+    pub async fn next(&mut self) -> Option<Output> {
+        self.output.recv().await
+    }
+
+    // Returns what has been written so far without waiting.
+    pub fn try_next(&mut self) -> Option<Output> {
+        self.output.recv().now_or_never().flatten()
+    }
+
+    fn send(&self, event: DeviceEvent) {
+        // The source has been dropped, which the test will notice by its output ending.
+        let _ = self.events.send(event);
+    }
+}
+
+pub struct MockSink {
+    leds: UnboundedReceiver<(DeviceId, Led, bool)>,
+    output: UnboundedSender<Output>,
+}
+
+impl MockSink {
+    fn output(&self, output: Output) -> Result<(), Error> {
+        let _ = self.output.send(output);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSink for MockSink {
+    async fn add_device(&mut self, id: DeviceId, info: DeviceInfo) -> Result<(), Error> {
+        self.output(Output::DeviceAdded { id, info })
+    }
+
+    async fn remove_device(&mut self, id: DeviceId) -> Result<(), Error> {
+        self.output(Output::DeviceRemoved { id })
+    }
+
+    async fn write(&mut self, event: Event) -> Result<(), Error> {
+        self.output(Output::Event {
+            device: None,
+            event,
+        })
+    }
+
+    async fn write_device(&mut self, id: DeviceId, event: Event) -> Result<(), Error> {
+        self.output(Output::Event {
+            device: Some(id),
+            event,
+        })
+    }
+
+    async fn read_led(&mut self) -> Result<(DeviceId, Led, bool), Error> {
+        match self.leds.recv().await {
+            Some(led) => Ok(led),
+            // Like the real devices, there might never be any LED changes.
+            None => futures::future::pending().await,
+        }
+    }
+}
+
+// Plays the part of the OS the mirrors are created on.
+pub struct SinkHandle {
+    leds: UnboundedSender<(DeviceId, Led, bool)>,
+    output: UnboundedReceiver<Output>,
+}
+
+impl SinkHandle {
+    // Changes a LED of a mirror as if the OS did it.
+    pub fn set_led(&self, id: DeviceId, led: Led, on: bool) {
+        let _ = self.leds.send((id, led, on));
+    }
+
+    // Returns what was written to the sink next, None if it has been dropped.
+    pub async fn next(&mut self) -> Option<Output> {
+        self.output.recv().await
+    }
+
+    // Returns what has been written so far without waiting.
+    pub fn try_next(&mut self) -> Option<Output> {
+        self.output.recv().now_or_never().flatten()
+    }
+}
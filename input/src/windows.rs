@@ -1,7 +1,11 @@
+mod device_list;
 mod event;
 mod event_manager;
+mod event_monitor;
 mod event_writer;
 mod oot;
 
+pub use device_list::list_devices;
 pub use event_manager::EventManager;
+pub use event_monitor::EventMonitor;
 pub use event_writer::EventWriter;
@@ -0,0 +1,6 @@
+use crate::device::DeviceEntry;
+use std::io::{Error, ErrorKind};
+
+pub async fn list_devices() -> Result<Vec<DeviceEntry>, Error> {
+    Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+}
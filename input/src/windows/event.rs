@@ -108,7 +108,10 @@ impl Event {
                         u
                     })])
                 }
+                // These are Linux key codes, which don't mean anything here.
+                KeyKind::Raw(_) => return None,
             },
+            Event::Absolute { .. } | Event::Sync => return None,
         };
 
         Some(inputs.map(|(type_, u)| INPUT { type_, u }))
@@ -94,7 +94,7 @@ impl Key {
             KpMinus => Oot::V1([(0x4A, false)]),
             KpPlus => Oot::V1([(0x4E, false)]),
             KpEnter => Oot::V1([(0x1C, true)]),
-            KpDott => Oot::V1([(0x1C, false)]),
+            KpDot => Oot::V1([(0x1C, false)]),
             Kp0 => Oot::V1([(0x52, false)]),
             Kp1 => Oot::V1([(0x4F, false)]),
             Kp2 => Oot::V1([(0x50, false)]),
@@ -1,3 +1,4 @@
+use crate::device::{DeviceEvent, DeviceId, Led};
 use crate::event::Event;
 use std::io::{Error, ErrorKind};
 
@@ -5,14 +6,30 @@ pub struct EventManager(());
 
 impl EventManager {
     pub async fn new() -> Result<Self, Error> {
-        Err(Error::new(ErrorKind::Other, "Not implemented"))
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
     }
 
-    pub async fn read(&mut self) -> Result<Event, Error> {
+    pub async fn passthrough() -> Result<Self, Error> {
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+    }
+
+    pub async fn read(&mut self) -> Result<DeviceEvent, Error> {
         todo!()
     }
 
     pub async fn write(&mut self, _event: Event) -> Result<(), Error> {
         todo!()
     }
+
+    pub async fn write_device(&mut self, _id: DeviceId, _event: Event) -> Result<(), Error> {
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+    }
+
+    pub async fn set_led(&mut self, _id: DeviceId, _led: Led, _on: bool) -> Result<(), Error> {
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+    }
+
+    pub async fn set_grabbed(&mut self, _grabbed: bool) -> Result<(), Error> {
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+    }
 }
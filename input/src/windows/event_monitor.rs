@@ -0,0 +1,14 @@
+use crate::device::DeviceEvent;
+use std::io::{Error, ErrorKind};
+
+pub struct EventMonitor(());
+
+impl EventMonitor {
+    pub async fn new() -> Result<Self, Error> {
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+    }
+
+    pub async fn read(&mut self) -> Result<DeviceEvent, Error> {
+        Err(Error::new(ErrorKind::Unsupported, "Not implemented"))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::device::{DeviceId, DeviceInfo, Led};
 use crate::event::{Direction, Event, Key, KeyKind};
 use std::io::Error;
 use std::time::{Duration, Instant};
@@ -36,6 +37,24 @@ impl EventWriter {
         self.event_sender.send(event).unwrap();
         Ok(())
     }
+
+    // There's no way to create virtual devices on Windows, so all events end up being sent the same way.
+    pub async fn add_device(&mut self, _id: DeviceId, _info: DeviceInfo) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub async fn remove_device(&mut self, _id: DeviceId) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub async fn write_device(&mut self, _id: DeviceId, event: Event) -> Result<(), Error> {
+        self.write(event).await
+    }
+
+    // Windows manages keyboard LEDs on its own, there are no changes to report.
+    pub async fn read_led(&mut self) -> Result<(DeviceId, Led, bool), Error> {
+        futures::future::pending().await
+    }
 }
 
 const REPEAT_INTERVAL: Duration = Duration::from_millis(20);
@@ -1,4 +1,6 @@
-use input::Event;
+pub mod record;
+
+use input::{DeviceId, DeviceInfo, Event, Led};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::io::{Error, ErrorKind};
@@ -6,7 +8,7 @@ use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 // Is it bold to assume there won't be more than 65536 protocol versions?
-pub const PROTOCOL_VERSION: u16 = 1;
+pub const PROTOCOL_VERSION: u16 = 9;
 pub const MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub async fn read_version<R>(mut reader: R) -> Result<u16, Error>
@@ -31,10 +33,10 @@ where
     R: AsyncRead + Unpin,
 {
     let length = {
-        let mut bytes = [0; 1];
+        let mut bytes = [0; 2];
         reader.read_exact(&mut bytes).await?;
 
-        bytes[0]
+        u16::from_le_bytes(bytes)
     };
 
     let mut data = vec![0; length as usize];
@@ -49,7 +51,7 @@ where
 {
     let data =
         bincode::serialize(&message).map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
-    let length: u8 = data
+    let length: u16 = data
         .len()
         .try_into()
         .map_err(|_| Error::new(ErrorKind::InvalidInput, "Serialized data is too large"))?;
@@ -59,9 +61,26 @@ where
     Ok(())
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Message {
-    Event(Event),
+    // Events without a device are written to a generic virtual device.
+    Event {
+        device: Option<DeviceId>,
+        event: Event,
+    },
     // Sent only to keep the connection alive.
     KeepAlive,
+    DeviceAdded {
+        id: DeviceId,
+        info: DeviceInfo,
+    },
+    DeviceRemoved {
+        id: DeviceId,
+    },
+    // Sent by clients when their OS changes a LED of a mirrored device.
+    Led {
+        device: DeviceId,
+        led: Led,
+        on: bool,
+    },
 }
@@ -0,0 +1,387 @@
+// Recordings of event streams, used to reproduce bugs and to automate demos.
+//
+// Recordings come in two formats, both starting with a header that identifies the format and its version:
+//
+// The binary format starts with the magic bytes "rkvmrec\0", the recording format version and the protocol version
+// (both u16, little endian). Each record is then stored as the time since the first one in microseconds (u64), the
+// length of the data (u16) and the record serialized with bincode, same as network messages. Since bincode doesn't
+// store names, a binary recording can only be read by rkvm with the same protocol version.
+//
+// The JSON lines format has a header line such as {"format":"rkvm-recording","version":2}, followed by a line per
+// record such as {"time":1500,"record":{"Event":{"device":3,"event":{"MouseMove":{"axis":"X","delta":-3}}}}}, using
+// the names from the serde derives of Record and Event. It's meant to be readable and editable by hand and keeps
+// working across protocol versions as long as the names don't change.
+//
+// Recordings start with the devices present at the time, so that replaying can recreate them. Events of absolute axes
+// and such only work on a device that has them.
+
+use crate::{Message, PROTOCOL_VERSION};
+use input::{DeviceId, DeviceInfo, Event};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::time::{self, Instant};
+
+// Bumped whenever the layout of the recordings changes, independently of the protocol version.
+pub const RECORDING_VERSION: u16 = 2;
+
+const MAGIC: &[u8; 8] = b"rkvmrec\0";
+const JSON_FORMAT: &str = "rkvm-recording";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Binary,
+    Json,
+}
+
+impl Format {
+    // Files ending with .jsonl or .json are JSON lines, anything else is binary.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("jsonl") | Some("json") => Format::Json,
+            _ => Format::Binary,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Record {
+    DeviceAdded {
+        id: DeviceId,
+        info: DeviceInfo,
+    },
+    DeviceRemoved {
+        id: DeviceId,
+    },
+    // Events without a device went to the generic device.
+    Event {
+        device: Option<DeviceId>,
+        event: Event,
+    },
+}
+
+impl Record {
+    // The part of a message that's worth recording, if any.
+    pub fn from_message(message: &Message) -> Option<Self> {
+        match message {
+            Message::DeviceAdded { id, info } => Some(Record::DeviceAdded {
+                id: *id,
+                info: info.clone(),
+            }),
+            Message::DeviceRemoved { id } => Some(Record::DeviceRemoved { id: *id }),
+            Message::Event { device, event } => Some(Record::Event {
+                device: *device,
+                event: *event,
+            }),
+            Message::KeepAlive | Message::Led { .. } => None,
+        }
+    }
+}
+
+impl From<Record> for Message {
+    fn from(record: Record) -> Self {
+        match record {
+            Record::DeviceAdded { id, info } => Message::DeviceAdded { id, info },
+            Record::DeviceRemoved { id } => Message::DeviceRemoved { id },
+            Record::Event { device, event } => Message::Event { device, event },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonHeader {
+    format: String,
+    version: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonRecord {
+    time: u64,
+    record: Record,
+}
+
+pub struct Recorder<W> {
+    writer: W,
+    format: Format,
+    // Time of the first event, which timestamps are relative to.
+    start: Option<Instant>,
+}
+
+impl<W> Recorder<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    pub async fn new(mut writer: W, format: Format) -> Result<Self, Error> {
+        match format {
+            Format::Binary => {
+                writer.write_all(MAGIC).await?;
+                writer.write_all(&RECORDING_VERSION.to_le_bytes()).await?;
+                writer.write_all(&PROTOCOL_VERSION.to_le_bytes()).await?;
+            }
+            Format::Json => {
+                let header = JsonHeader {
+                    format: JSON_FORMAT.to_owned(),
+                    version: RECORDING_VERSION,
+                };
+                write_json_line(&mut writer, &header).await?;
+            }
+        }
+
+        writer.flush().await?;
+        Ok(Self {
+            writer,
+            format,
+            start: None,
+        })
+    }
+
+    pub async fn write(&mut self, record: Record) -> Result<(), Error> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let time = start.elapsed().as_micros() as u64;
+
+        match self.format {
+            Format::Binary => {
+                let data = bincode::serialize(&record)
+                    .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+                let length: u16 = data.len().try_into().map_err(|_| {
+                    Error::new(ErrorKind::InvalidInput, "Serialized data is too large")
+                })?;
+
+                self.writer.write_all(&time.to_le_bytes()).await?;
+                self.writer.write_all(&length.to_le_bytes()).await?;
+                self.writer.write_all(&data).await?;
+            }
+            Format::Json => write_json_line(&mut self.writer, &JsonRecord { time, record }).await?,
+        }
+
+        // Flush right away, recordings are usually stopped by killing the program.
+        self.writer.flush().await
+    }
+}
+
+pub struct Player<R> {
+    reader: BufReader<R>,
+    format: Format,
+    speed: f64,
+    // When the first event was played back.
+    start: Option<Instant>,
+}
+
+impl<R> Player<R>
+where
+    R: AsyncRead + Unpin,
+{
+    // Records are played back at the given multiple of the original speed.
+    pub async fn new(reader: R, format: Format, speed: f64) -> Result<Self, Error> {
+        if !(speed > 0.0 && speed.is_finite()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Speed must be a positive number",
+            ));
+        }
+
+        let mut reader = BufReader::new(reader);
+        match format {
+            Format::Binary => {
+                let mut magic = [0; 8];
+                reader.read_exact(&mut magic).await?;
+                if &magic != MAGIC {
+                    return Err(Error::new(ErrorKind::InvalidData, "Not an rkvm recording"));
+                }
+
+                check_version(reader.read_u16_le().await?)?;
+
+                let protocol = reader.read_u16_le().await?;
+                if protocol != PROTOCOL_VERSION {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Recorded with protocol version {}, expecting {}",
+                            protocol, PROTOCOL_VERSION
+                        ),
+                    ));
+                }
+            }
+            Format::Json => {
+                let header: JsonHeader = match read_json_line(&mut reader).await? {
+                    Some(header) => header,
+                    None => return Err(Error::new(ErrorKind::UnexpectedEof, "Empty recording")),
+                };
+
+                if header.format != JSON_FORMAT {
+                    return Err(Error::new(ErrorKind::InvalidData, "Not an rkvm recording"));
+                }
+
+                check_version(header.version)?;
+            }
+        }
+
+        Ok(Self {
+            reader,
+            format,
+            speed,
+            start: None,
+        })
+    }
+
+    // Returns the next record once it's due, or None at the end of the recording.
+    pub async fn read(&mut self) -> Result<Option<Record>, Error> {
+        let (time, record) = match self.format {
+            Format::Binary => {
+                let time = match self.reader.read_u64_le().await {
+                    Ok(time) => time,
+                    Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(err) => return Err(err),
+                };
+
+                let length = self.reader.read_u16_le().await?;
+                let mut data = vec![0; length as usize];
+                self.reader.read_exact(&mut data).await?;
+
+                let record = bincode::deserialize(&data)
+                    .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+                (time, record)
+            }
+            Format::Json => match read_json_line::<_, JsonRecord>(&mut self.reader).await? {
+                Some(record) => (record.time, record.record),
+                None => return Ok(None),
+            },
+        };
+
+        // Hand edited recordings can have any time at all.
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let due =
+            Duration::try_from_secs_f64(Duration::from_micros(time).as_secs_f64() / self.speed)
+                .ok()
+                .and_then(|delay| start.checked_add(delay))
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Time {} out of range", time),
+                    )
+                })?;
+        time::sleep_until(due).await;
+
+        Ok(Some(record))
+    }
+}
+
+fn check_version(version: u16) -> Result<(), Error> {
+    if version != RECORDING_VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unsupported recording version {}, expecting {}",
+                version, RECORDING_VERSION
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn write_json_line<W, T>(writer: &mut W, value: &T) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line =
+        serde_json::to_vec(value).map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+    line.push(b'\n');
+
+    writer.write_all(&line).await
+}
+
+// Returns None at the end of the file, empty lines are skipped.
+async fn read_json_line<R, T>(reader: &mut BufReader<R>) -> Result<Option<T>, Error>
+where
+    R: AsyncRead + Unpin,
+    T: for<'de> Deserialize<'de>,
+{
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{Axis, Key, KeyKind};
+
+    #[tokio::test]
+    async fn records_survive_both_formats() {
+        let id: DeviceId = serde_json::from_str("3").unwrap();
+        let records = vec![
+            Record::DeviceAdded {
+                id,
+                info: DeviceInfo {
+                    name: "Mouse".to_owned(),
+                    relative: vec![Axis::X, Axis::Y],
+                    ..Default::default()
+                },
+            },
+            Record::Event {
+                device: Some(id),
+                event: Event::MouseMove {
+                    axis: Axis::X,
+                    delta: -3,
+                },
+            },
+            Record::Event {
+                device: None,
+                event: Event::Key {
+                    direction: input::Direction::Down,
+                    kind: KeyKind::Key(Key::A),
+                },
+            },
+            Record::DeviceRemoved { id },
+        ];
+
+        for format in [Format::Binary, Format::Json].iter() {
+            let mut recorder = Recorder::new(Vec::new(), *format).await.unwrap();
+            for record in &records {
+                recorder.write(record.clone()).await.unwrap();
+            }
+
+            let data = recorder.writer;
+            let mut player = Player::new(data.as_slice(), *format, 1000.0).await.unwrap();
+            let mut played = Vec::new();
+            while let Some(record) = player.read().await.unwrap() {
+                played.push(record);
+            }
+
+            assert_eq!(played, records);
+        }
+    }
+
+    #[tokio::test]
+    async fn times_out_of_range_are_errors() {
+        let data = format!(
+            "{{\"format\":\"{}\",\"version\":{}}}\n{{\"time\":{},\"record\":{{\"DeviceRemoved\":{{\"id\":3}}}}}}\n",
+            JSON_FORMAT,
+            RECORDING_VERSION,
+            u64::MAX
+        );
+
+        let mut player = Player::new(data.as_bytes(), Format::Json, 1e-10)
+            .await
+            .unwrap();
+        let err = player.read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
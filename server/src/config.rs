@@ -1,15 +1,153 @@
-use input::Key;
-use serde::Deserialize;
-use std::collections::HashSet;
-use std::net::SocketAddr;
+use crate::macros::{self, Macros};
+use crate::pointer::PointerConfig;
+use crate::remap::Table;
+use input::{Button, Key, KeyKind};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Config {
     pub listen_address: SocketAddr,
+    #[serde(deserialize_with = "deserialize_keys")]
     pub switch_keys: HashSet<Key>,
     pub identity_path: PathBuf,
     #[serde(default)]
     pub identity_password: String,
+    #[serde(default)]
+    pub forward_gamepads: bool,
+    // Only grab devices while a client is active.
+    #[serde(default)]
+    pub passthrough: bool,
+    // Keys that play a sequence of events instead of being sent anywhere.
+    #[serde(default, deserialize_with = "deserialize_macros")]
+    pub macros: Macros,
+    // Script run on every event, see script.rs.
+    pub script: Option<PathBuf>,
+    // Settings of individual clients, identified by their IP address.
+    #[serde(default)]
+    pub clients: HashMap<IpAddr, ClientConfig>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClientConfig {
+    #[serde(default, deserialize_with = "deserialize_remap")]
+    pub remap: Table,
+    // Mouse buttons to swap or replace, such as Left = "Right" and Right = "Left" for left-handed use.
+    #[serde(default, deserialize_with = "deserialize_buttons")]
+    pub buttons: HashMap<Button, Button>,
+    #[serde(default)]
+    pub pointer: PointerConfig,
+}
+
+impl ClientConfig {
+    // The remap table with the button mapping applied, explicit remaps of buttons take precedence.
+    pub fn remap_table(&self) -> Table {
+        let mut table = self.remap.clone();
+        for (from, to) in &self.buttons {
+            table
+                .entry(KeyKind::Button(*from))
+                .or_insert_with(|| vec![KeyKind::Button(*to)]);
+        }
+
+        table
+    }
+}
+
+// Accepts either a single chord such as "Ctrl+Alt+Right" or a list of key names (which can be chords too).
+fn deserialize_keys<'de, D>(deserializer: D) -> Result<HashSet<Key>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        Chord(String),
+        List(Vec<String>),
+    }
+
+    let chords = match Keys::deserialize(deserializer)? {
+        Keys::Chord(chord) => vec![chord],
+        Keys::List(list) => list,
+    };
+
+    let mut keys = HashSet::new();
+    for chord in &chords {
+        for kind in input::parse_chord(chord).map_err(D::Error::custom)? {
+            match kind {
+                KeyKind::Key(key) => {
+                    keys.insert(key);
+                }
+                kind => {
+                    return Err(D::Error::custom(format!(
+                        "\"{}\" is not a keyboard key",
+                        kind
+                    )))
+                }
+            }
+        }
+    }
+
+    Ok(keys)
+}
+
+// Maps key names to chords, an empty string disables the key.
+fn deserialize_remap<'de, D>(deserializer: D) -> Result<Table, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut table = Table::new();
+    for (from, to) in HashMap::<String, String>::deserialize(deserializer)? {
+        let from = from.parse().map_err(D::Error::custom)?;
+        let to = if to.trim().is_empty() {
+            Vec::new()
+        } else {
+            input::parse_chord(&to).map_err(D::Error::custom)?
+        };
+
+        table.insert(from, to);
+    }
+
+    Ok(table)
+}
+
+fn deserialize_buttons<'de, D>(deserializer: D) -> Result<HashMap<Button, Button>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // The prefix is redundant here, but allow it for consistency with the rest of the config.
+    let parse = |name: &str| {
+        let name = name.trim();
+        name.strip_prefix("Button::")
+            .unwrap_or(name)
+            .parse::<Button>()
+            .map_err(D::Error::custom)
+    };
+
+    let mut buttons = HashMap::new();
+    for (from, to) in HashMap::<String, String>::deserialize(deserializer)? {
+        buttons.insert(parse(&from)?, parse(&to)?);
+    }
+
+    Ok(buttons)
+}
+
+fn deserialize_macros<'de, D>(deserializer: D) -> Result<Macros, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut macros = Macros::new();
+    for (key, steps) in HashMap::<String, Vec<String>>::deserialize(deserializer)? {
+        let key = key.parse().map_err(D::Error::custom)?;
+        let steps = macros::parse(&steps).map_err(D::Error::custom)?;
+
+        macros.insert(key, Arc::new(steps));
+    }
+
+    Ok(macros)
 }
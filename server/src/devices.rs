@@ -0,0 +1,125 @@
+use crate::config::Config;
+use anyhow::Error;
+use input::{DeviceInfo, KeyKind};
+
+// Prints input devices of the system and what rkvm would do with them.
+pub async fn run(config: &Config) -> Result<(), Error> {
+    for entry in input::list_devices().await? {
+        println!("{}", entry.path.display());
+
+        let info = match entry.info {
+            Ok(info) => info,
+            Err(err) => {
+                println!("  Failed to open: {}", err);
+                println!();
+                continue;
+            }
+        };
+
+        println!("  Name: {}", info.name);
+        println!(
+            "  Vendor: {:#06x}, product: {:#06x}, version: {:#06x}, bus type: {:#06x}",
+            info.vendor, info.product, info.version, info.bustype
+        );
+
+        if !info.phys.is_empty() {
+            println!("  Phys: {}", info.phys);
+        }
+
+        println!("  Capabilities:");
+        for capability in capabilities(&info) {
+            println!("    {}", capability);
+        }
+
+        let grabbed = if config.passthrough {
+            "grabbed while a client is active"
+        } else {
+            "grabbed"
+        };
+        let status = if entry.own {
+            "ignored, this is one of rkvm's own virtual devices".to_owned()
+        } else if crate::is_forwarded(&info, config.forward_gamepads) {
+            format!("{}, events go to the active client", grabbed)
+        } else {
+            format!("{}, events stay local (see forward-gamepads)", grabbed)
+        };
+
+        println!("  Status: {}", status);
+        println!();
+    }
+
+    Ok(())
+}
+
+fn capabilities(info: &DeviceInfo) -> Vec<String> {
+    let mut capabilities = Vec::new();
+
+    let keys = info
+        .keys
+        .iter()
+        .filter(|kind| !matches!(kind, KeyKind::Button(_)))
+        .count();
+    if keys > 0 {
+        capabilities.push(format!("{} keys", keys));
+    }
+
+    let buttons = info
+        .keys
+        .iter()
+        .filter(|kind| matches!(kind, KeyKind::Button(_)))
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    if !buttons.is_empty() {
+        capabilities.push(format!("Buttons: {}", buttons.join(", ")));
+    }
+
+    if !info.relative.is_empty() {
+        let axes = info
+            .relative
+            .iter()
+            .map(|axis| format!("{:?}", axis))
+            .collect::<Vec<_>>();
+        capabilities.push(format!("Relative axes: {}", axes.join(", ")));
+    }
+
+    if info.scroll {
+        capabilities.push("Scroll wheel".to_owned());
+    }
+
+    if !info.absolute.is_empty() {
+        let axes = info
+            .absolute
+            .iter()
+            .map(|(axis, info)| format!("{:?} ({}..={})", axis, info.minimum, info.maximum))
+            .collect::<Vec<_>>();
+        capabilities.push(format!("Absolute axes: {}", axes.join(", ")));
+    }
+
+    if !info.properties.is_empty() {
+        let properties = info
+            .properties
+            .iter()
+            .map(|property| format!("{:?}", property))
+            .collect::<Vec<_>>();
+        capabilities.push(format!("Properties: {}", properties.join(", ")));
+    }
+
+    if !info.leds.is_empty() {
+        let leds = info
+            .leds
+            .iter()
+            .map(|led| format!("{:?}", led))
+            .collect::<Vec<_>>();
+        capabilities.push(format!("LEDs: {}", leds.join(", ")));
+    }
+
+    if info.gamepad {
+        capabilities.push("Gamepad or joystick".to_owned());
+    }
+
+    if capabilities.is_empty() {
+        capabilities.push("None that rkvm understands".to_owned());
+    }
+
+    capabilities
+}
@@ -0,0 +1,52 @@
+use anyhow::Error;
+use input::{DeviceEvent, Direction, Event, EventMonitor, KeyKind};
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+// Prints keys as they are pressed, in the form they can be written in the config.
+pub async fn run() -> Result<Infallible, Error> {
+    let mut monitor = EventMonitor::new().await?;
+    let mut names = HashMap::new();
+    // Keys which are currently held down, in the order they were pressed.
+    let mut pressed: Vec<KeyKind> = Vec::new();
+
+    log::info!("Press keys to print their names, Ctrl+C to exit");
+
+    loop {
+        let (id, direction, kind) = match monitor.read().await? {
+            DeviceEvent::Added { id, info } => {
+                names.insert(id, info.name);
+                continue;
+            }
+            DeviceEvent::Removed { id } => {
+                names.remove(&id);
+                continue;
+            }
+            DeviceEvent::Event {
+                id,
+                event: Event::Key { direction, kind },
+            } => (id, direction, kind),
+            _ => continue,
+        };
+
+        if direction == Direction::Up {
+            pressed.retain(|other| *other != kind);
+            continue;
+        }
+
+        if !pressed.contains(&kind) {
+            pressed.push(kind);
+        }
+
+        let chord = pressed
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("+");
+        let name = names
+            .get(&id)
+            .map(String::as_str)
+            .unwrap_or("unknown device");
+        println!("{:<40} ({})", chord, name);
+    }
+}
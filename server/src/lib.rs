@@ -0,0 +1,798 @@
+pub mod config;
+pub mod devices;
+pub mod keys;
+mod macros;
+mod pointer;
+pub mod record;
+mod remap;
+pub mod replay;
+mod script;
+
+use anyhow::{Context, Error};
+use config::Config;
+use input::{
+    DeviceEvent, DeviceId, DeviceInfo, Direction, Event, EventManager, EventSource, KeyKind, Led,
+};
+use macros::MacroEngine;
+use net::{self, Message, PROTOCOL_VERSION};
+use pointer::Pointer;
+use remap::Remapper;
+use script::Script;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncWrite, ReadHalf, WriteHalf};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time;
+use tokio_native_tls::native_tls::{Identity, TlsAcceptor};
+
+type Leds = HashMap<(DeviceId, Led), bool>;
+
+// A newly connected client, identified by a number unique for as long as the server runs.
+type NewClient = (usize, IpAddr, UnboundedSender<Message>);
+
+// How often the script is checked for changes.
+const SCRIPT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Client {
+    id: usize,
+    sender: UnboundedSender<Message>,
+    // LED state the client's OS wants its mirrors of our devices to show.
+    leds: Leds,
+    remapper: Remapper,
+    pointer: Pointer,
+}
+
+impl Client {
+    fn send(&self, events: Vec<(Option<DeviceId>, Event)>) -> bool {
+        events
+            .into_iter()
+            .all(|(device, event)| self.sender.send(Message::Event { device, event }).is_ok())
+    }
+}
+
+async fn handle_connection<T>(
+    mut stream: T,
+    receiver: UnboundedReceiver<Message>,
+    id: usize,
+    leds: UnboundedSender<(usize, DeviceId, Led, bool)>,
+) -> Result<(), Error>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    net::write_version(&mut stream, PROTOCOL_VERSION).await?;
+
+    let version = net::read_version(&mut stream).await?;
+    if version != PROTOCOL_VERSION {
+        return Err(anyhow::anyhow!(
+            "Incompatible protocol version (got {}, expecting {})",
+            version,
+            PROTOCOL_VERSION
+        ));
+    }
+
+    let (read_stream, write_stream) = tokio::io::split(stream);
+    tokio::select! {
+        result = read_messages(read_stream, id, leds) => result,
+        result = write_messages(write_stream, receiver) => result,
+    }
+}
+
+async fn read_messages<T>(
+    mut stream: ReadHalf<T>,
+    id: usize,
+    leds: UnboundedSender<(usize, DeviceId, Led, bool)>,
+) -> Result<(), Error>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    loop {
+        // Clients only send LED changes, which can take arbitrarily long, so there's no timeout.
+        if let Message::Led { device, led, on } = net::read_message(&mut stream).await? {
+            let _ = leds.send((id, device, led, on));
+        }
+    }
+}
+
+async fn write_messages<T>(
+    mut stream: WriteHalf<T>,
+    mut receiver: UnboundedReceiver<Message>,
+) -> Result<(), Error>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    loop {
+        // Send a keep alive message in intervals of half of the timeout just to be on the safe side.
+        let message = match time::timeout(net::MESSAGE_TIMEOUT / 2, receiver.recv()).await {
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(()),
+            Err(_) => Message::KeepAlive,
+        };
+
+        time::timeout(
+            net::MESSAGE_TIMEOUT,
+            net::write_message(&mut stream, &message),
+        )
+        .await
+        .context("Write timeout")??;
+    }
+}
+
+// Runs the server with the input devices of this machine.
+pub async fn run(config: &Config) -> Result<Infallible, Error> {
+    let acceptor = tls_acceptor(config).await?;
+    let listener = TcpListener::bind(config.listen_address).await?;
+    let manager = if config.passthrough {
+        EventManager::passthrough().await?
+    } else {
+        EventManager::new().await?
+    };
+
+    serve(config, listener, acceptor, manager).await
+}
+
+// Accepts clients on the listener and sends them events read from the source.
+pub async fn serve<S: EventSource>(
+    config: &Config,
+    listener: TcpListener,
+    acceptor: tokio_native_tls::TlsAcceptor,
+    source: S,
+) -> Result<Infallible, Error> {
+    log::info!("Listening on {}", listener.local_addr()?);
+
+    let (client_sender, client_receiver) = mpsc::unbounded_channel();
+    let (led_sender, led_receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for id in 0.. {
+            let (stream, address) = match listener.accept().await {
+                Ok(sa) => sa,
+                Err(err) => {
+                    let _ = client_sender.send(Err(err));
+                    return;
+                }
+            };
+
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::error!("{}: TLS error: {}", address, err);
+                    continue;
+                }
+            };
+
+            let (sender, receiver) = mpsc::unbounded_channel();
+            if client_sender.send(Ok((id, address.ip(), sender))).is_err() {
+                return;
+            }
+
+            let led_sender = led_sender.clone();
+            tokio::spawn(async move {
+                log::info!("{}: connected", address);
+                let message = handle_connection(stream, receiver, id, led_sender)
+                    .await
+                    .err()
+                    .map(|err| format!(" ({})", err))
+                    .unwrap_or_else(String::new);
+                log::info!("{}: disconnected{}", address, message);
+            });
+        }
+    });
+
+    route(config, source, client_receiver, led_receiver).await
+}
+
+// Routes events from the source to the active client or back to the source.
+async fn route<S: EventSource>(
+    config: &Config,
+    mut source: S,
+    mut client_receiver: UnboundedReceiver<Result<NewClient, io::Error>>,
+    mut led_receiver: UnboundedReceiver<(usize, DeviceId, Led, bool)>,
+) -> Result<Infallible, Error> {
+    let mut script = match &config.script {
+        Some(path) => Some(Script::load(path).await?),
+        None => None,
+    };
+    let mut clients: Vec<Client> = Vec::new();
+    let mut current = 0;
+    // Devices that are mirrored on clients.
+    let mut devices = HashMap::new();
+    // LED state our own OS wants the devices to show.
+    let mut local_leds = Leds::new();
+    let mut macros = MacroEngine::new(config.macros.clone());
+    let mut reload = time::interval(SCRIPT_RELOAD_INTERVAL);
+    let mut key_states: HashMap<_, _> = config
+        .switch_keys
+        .iter()
+        .copied()
+        .map(|key| (key, false))
+        .collect();
+    loop {
+        tokio::select! {
+            event = source.read() => {
+                let (id, event) = match event? {
+                    DeviceEvent::Added { id, info } => {
+                        if is_forwarded(&info, config.forward_gamepads) {
+                            for client in &clients {
+                                let _ = client.sender.send(Message::DeviceAdded { id, info: info.clone() });
+                            }
+
+                            devices.insert(id, info);
+                        }
+
+                        continue;
+                    }
+                    DeviceEvent::Removed { id } => {
+                        if devices.remove(&id).is_some() {
+                            for client in &mut clients {
+                                let events = client.remapper.remove_device(id);
+                                client.send(events);
+                                let _ = client.sender.send(Message::DeviceRemoved { id });
+                            }
+                        }
+
+                        local_leds.retain(|(device, _), _| *device != id);
+                        for client in &mut clients {
+                            client.leds.retain(|(device, _), _| *device != id);
+                        }
+
+                        continue;
+                    }
+                    DeviceEvent::Led { id, led, on } => {
+                        local_leds.insert((id, led), on);
+                        if current == 0 {
+                            source.set_led(id, led, on).await?;
+                        }
+
+                        continue;
+                    }
+                    DeviceEvent::Event { id, event } => (id, event),
+                };
+
+                if let Event::Key { direction, kind: KeyKind::Key(key) } = event {
+                    if let Some(state) = key_states.get_mut(&key) {
+                        *state = direction == Direction::Down;
+                    }
+                }
+
+                // TODO: This won't work with multiple keys.
+                if key_states.iter().filter(|(_, state)| **state).count() == key_states.len() {
+                    for state in key_states.values_mut() {
+                        *state = false;
+                    }
+
+                    // Don't leave keys held on the client we're leaving.
+                    if current != 0 {
+                        let client = &mut clients[current - 1];
+                        let events = client.remapper.release_all();
+                        client.send(events);
+                    }
+
+                    current = (current + 1) % (clients.len() + 1);
+                    log::info!("Switching to client {}", current);
+
+                    let leds = match current {
+                        0 => &local_leds,
+                        current => &clients[current - 1].leds,
+                    };
+
+                    set_leds(&mut source, &devices, leds).await?;
+                    set_grabbed(config, &mut source, current).await?;
+                    continue;
+                }
+
+                // Devices aren't grabbed then, so the rest of the system has the event already.
+                if config.passthrough && current == 0 {
+                    continue;
+                }
+
+                let events = match &mut script {
+                    Some(script) => script.run(devices.get(&id), event, current, clients.len()),
+                    None => vec![(current, event)],
+                };
+
+                // Refer to clients by their ID, since indices shift when one disconnects.
+                let events = events
+                    .into_iter()
+                    .map(|(target, event)| (target.checked_sub(1).map(|idx| clients[idx].id), event))
+                    .collect::<Vec<_>>();
+
+                for (target, event) in events {
+                    if macros.handle(event) {
+                        continue;
+                    }
+
+                    let idx = target.and_then(|target| clients.iter().position(|client| client.id == target));
+                    // Devices which aren't forwarded have no info here.
+                    let info = devices.get(&id).filter(|_| idx.is_some());
+                    if let (Some(idx), Some(info)) = (idx, info) {
+                        let client = &mut clients[idx];
+                        let events = client
+                            .pointer
+                            .scale(id, event)
+                            .into_iter()
+                            // Only groups of absolute events need to be marked on the other side.
+                            .filter(|event| !matches!(event, Event::Sync) || !info.absolute.is_empty())
+                            .flat_map(|event| client.remapper.remap(id, info, event))
+                            .collect::<Vec<_>>();
+                        if client.send(events) {
+                            continue;
+                        }
+
+                        clients.remove(idx);
+                        if current != idx + 1 {
+                            // Events the script meant for another client are dropped along with it.
+                            if current > idx + 1 {
+                                current -= 1;
+                            }
+
+                            continue;
+                        }
+
+                        current = 0;
+                        set_leds(&mut source, &devices, &local_leds).await?;
+                        set_grabbed(config, &mut source, current).await?;
+                    } else if target.is_some() && idx.is_none() {
+                        // The client disconnected while handling the previous events.
+                        continue;
+                    }
+
+                    source.write_device(id, event).await?;
+                }
+            }
+            _ = reload.tick(), if script.is_some() => {
+                script.as_mut().unwrap().reload_if_modified().await;
+            }
+            event = macros.read() => {
+                // Macros can contain any key, so they go to the generic device.
+                if current != 0 {
+                    let idx = current - 1;
+                    if clients[idx].sender.send(Message::Event { device: None, event }).is_ok() {
+                        continue;
+                    }
+
+                    clients.remove(idx);
+                    current = 0;
+                    set_leds(&mut source, &devices, &local_leds).await?;
+                    set_grabbed(config, &mut source, current).await?;
+                }
+
+                source.write(event).await?;
+            }
+            client = client_receiver.recv() => {
+                let (id, address, sender) = client.unwrap()?;
+                for (id, info) in &devices {
+                    let _ = sender.send(Message::DeviceAdded { id: *id, info: info.clone() });
+                }
+
+                let client_config = config.clients.get(&address);
+                let remap = client_config
+                    .map(|config| config.remap_table())
+                    .unwrap_or_default();
+                let pointer = client_config
+                    .map(|config| config.pointer.clone())
+                    .unwrap_or_default();
+
+                clients.push(Client {
+                    id,
+                    sender,
+                    leds: Leds::new(),
+                    remapper: Remapper::new(remap),
+                    pointer: Pointer::new(pointer),
+                });
+            }
+            led = led_receiver.recv() => {
+                // The listening task holds a sender for as long as it's running.
+                let (id, device, led, on) = match led {
+                    Some(led) => led,
+                    None => continue,
+                };
+
+                let idx = match clients.iter().position(|client| client.id == id) {
+                    Some(idx) => idx,
+                    None => continue,
+                };
+
+                clients[idx].leds.insert((device, led), on);
+                if current == idx + 1 {
+                    source.set_led(device, led, on).await?;
+                }
+            }
+        }
+    }
+}
+
+pub async fn tls_acceptor(config: &Config) -> Result<tokio_native_tls::TlsAcceptor, Error> {
+    let identity = fs::read(&config.identity_path)
+        .await
+        .context("Failed to read identity")?;
+    let identity = Identity::from_pkcs12(&identity, &config.identity_password)
+        .context("Failed to parse identity")?;
+
+    TlsAcceptor::new(identity)
+        .context("Failed to create TLS acceptor")
+        .map(Into::into)
+}
+
+// Whether events of the device should go to clients.
+fn is_forwarded(info: &DeviceInfo, forward_gamepads: bool) -> bool {
+    // Gamepads stay local unless configured otherwise.
+    !info.gamepad || forward_gamepads
+}
+
+// Shows the LED state of a machine on the devices. Every LED of a device is set, since those the machine doesn't know
+// about might have been turned on by another one.
+async fn set_leds<S: EventSource>(
+    source: &mut S,
+    devices: &HashMap<DeviceId, DeviceInfo>,
+    leds: &Leds,
+) -> Result<(), Error> {
+    for (id, info) in devices {
+        for led in &info.leds {
+            let on = leds.get(&(*id, *led)).copied().unwrap_or(false);
+            source.set_led(*id, *led, on).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// In passthrough mode, devices are only grabbed while a client is active.
+async fn set_grabbed<S: EventSource>(
+    config: &Config,
+    source: &mut S,
+    current: usize,
+) -> Result<(), Error> {
+    if config.passthrough {
+        source.set_grabbed(current != 0).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::mock::{self, Output, SourceHandle};
+    use input::{Axis, Button, Key};
+    use tokio::task::JoinHandle;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    // The server's routing with a mock source and without any networking.
+    struct Server {
+        source: SourceHandle,
+        clients: UnboundedSender<Result<NewClient, io::Error>>,
+        // LED changes reported by clients, like the listening task forwards them.
+        leds: UnboundedSender<(usize, DeviceId, Led, bool)>,
+        task: JoinHandle<Result<Infallible, Error>>,
+    }
+
+    impl Server {
+        fn start(extra_config: &str) -> Self {
+            let config = format!(
+                "listen-address = \"127.0.0.1:0\"\nswitch-keys = \"ScrollLock\"\nidentity-path = \"\"\n{}",
+                extra_config
+            );
+            let config: Config = toml::from_str(&config).unwrap();
+
+            let (source, handle) = mock::source();
+            let (client_sender, client_receiver) = mpsc::unbounded_channel();
+            let (led_sender, led_receiver) = mpsc::unbounded_channel();
+            let task =
+                tokio::spawn(
+                    async move { route(&config, source, client_receiver, led_receiver).await },
+                );
+
+            Self {
+                source: handle,
+                clients: client_sender,
+                leds: led_sender,
+                task,
+            }
+        }
+
+        fn connect(&self, id: usize) -> UnboundedReceiver<Message> {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            self.clients
+                .send(Ok((id, "127.0.0.1".parse().unwrap(), sender)))
+                .unwrap();
+
+            receiver
+        }
+
+        fn add_keyboard(&self) -> DeviceId {
+            self.source.add_device(DeviceInfo {
+                name: "Keyboard".to_owned(),
+                keys: [Key::A, Key::B, Key::ScrollLock]
+                    .iter()
+                    .map(|key| KeyKind::Key(*key))
+                    .collect(),
+                ..Default::default()
+            })
+        }
+
+        fn tap(&self, id: DeviceId, key: Key) {
+            self.source.event(id, key_event(key, Direction::Down));
+            self.source.event(id, key_event(key, Direction::Up));
+        }
+
+        async fn next_local(&mut self) -> Output {
+            time::timeout(TIMEOUT, self.source.next())
+                .await
+                .unwrap()
+                .unwrap()
+        }
+    }
+
+    impl Drop for Server {
+        fn drop(&mut self) {
+            self.task.abort();
+        }
+    }
+
+    fn key_event(key: Key, direction: Direction) -> Event {
+        Event::Key {
+            direction,
+            kind: KeyKind::Key(key),
+        }
+    }
+
+    // Skips messages other than events.
+    async fn next_event(receiver: &mut UnboundedReceiver<Message>) -> (Option<DeviceId>, Event) {
+        loop {
+            let message = time::timeout(TIMEOUT, receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let Message::Event { device, event } = message {
+                return (device, event);
+            }
+        }
+    }
+
+    async fn wait_for_device(receiver: &mut UnboundedReceiver<Message>) -> DeviceId {
+        loop {
+            let message = time::timeout(TIMEOUT, receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let Message::DeviceAdded { id, .. } = message {
+                return id;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn events_stay_local_without_clients() {
+        let mut server = Server::start("");
+        let id = server.add_keyboard();
+        server.source.event(id, key_event(Key::A, Direction::Down));
+
+        assert_eq!(
+            server.next_local().await,
+            Output::Event {
+                device: Some(id),
+                event: key_event(Key::A, Direction::Down),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn switch_keys_move_events_between_machines() {
+        let mut server = Server::start("");
+        let mut client = server.connect(0);
+        let id = server.add_keyboard();
+        assert_eq!(wait_for_device(&mut client).await, id);
+
+        // The press switches to the client, which then gets the release.
+        server.tap(id, Key::ScrollLock);
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::ScrollLock, Direction::Up))
+        );
+
+        server.source.event(id, key_event(Key::A, Direction::Down));
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::A, Direction::Down))
+        );
+
+        // Switching back releases the key held on the client.
+        server
+            .source
+            .event(id, key_event(Key::ScrollLock, Direction::Down));
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::A, Direction::Up))
+        );
+
+        server.source.event(id, key_event(Key::B, Direction::Down));
+        assert_eq!(
+            server.next_local().await,
+            Output::Event {
+                device: Some(id),
+                event: key_event(Key::B, Direction::Down),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn mouse_frames_are_not_marked_for_clients() {
+        let server = Server::start("");
+        let mut client = server.connect(0);
+        let keyboard = server.add_keyboard();
+        let mouse = server.source.add_device(DeviceInfo {
+            name: "Mouse".to_owned(),
+            keys: vec![KeyKind::Button(Button::Left)],
+            relative: vec![Axis::X, Axis::Y],
+            ..Default::default()
+        });
+        wait_for_device(&mut client).await;
+        wait_for_device(&mut client).await;
+
+        server.tap(keyboard, Key::ScrollLock);
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(keyboard), key_event(Key::ScrollLock, Direction::Up))
+        );
+
+        let movement = Event::MouseMove {
+            axis: Axis::X,
+            delta: 3,
+        };
+        server.source.event(mouse, movement);
+        server.source.event(mouse, Event::Sync);
+        server.tap(keyboard, Key::A);
+        assert_eq!(next_event(&mut client).await, (Some(mouse), movement));
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(keyboard), key_event(Key::A, Direction::Down))
+        );
+    }
+
+    #[tokio::test]
+    async fn passthrough_grabs_only_while_a_client_is_active() {
+        let mut server = Server::start("passthrough = true");
+        let mut client = server.connect(0);
+        let id = server.add_keyboard();
+        wait_for_device(&mut client).await;
+
+        server.tap(id, Key::ScrollLock);
+        assert_eq!(server.next_local().await, Output::Grabbed { grabbed: true });
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::ScrollLock, Direction::Up))
+        );
+
+        server.tap(id, Key::ScrollLock);
+        assert_eq!(
+            server.next_local().await,
+            Output::Grabbed { grabbed: false }
+        );
+    }
+
+    #[tokio::test]
+    async fn passthrough_leaves_local_input_alone() {
+        let mut server = Server::start("passthrough = true\n[macros]\nA = [\"B\"]");
+        let mut client = server.connect(0);
+        let id = server.add_keyboard();
+        wait_for_device(&mut client).await;
+
+        // Neither the macro nor writing the events back applies to input the system has seen.
+        server.tap(id, Key::A);
+        server.tap(id, Key::B);
+        server.tap(id, Key::ScrollLock);
+        assert_eq!(server.next_local().await, Output::Grabbed { grabbed: true });
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::ScrollLock, Direction::Up))
+        );
+
+        server.tap(id, Key::B);
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::B, Direction::Down))
+        );
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::B, Direction::Up))
+        );
+
+        // Input going to the client still triggers it.
+        server.tap(id, Key::A);
+        assert_eq!(
+            next_event(&mut client).await,
+            (None, key_event(Key::B, Direction::Down))
+        );
+    }
+
+    #[tokio::test]
+    async fn leds_follow_the_active_machine() {
+        let mut server = Server::start("");
+        let mut client = server.connect(0);
+        let id = server.source.add_device(DeviceInfo {
+            name: "Keyboard".to_owned(),
+            keys: vec![KeyKind::Key(Key::ScrollLock)],
+            leds: vec![Led::CapsLock, Led::NumLock],
+            ..Default::default()
+        });
+        wait_for_device(&mut client).await;
+
+        // The OS of this machine turns Num Lock on.
+        server.source.set_led(id, Led::NumLock, true);
+        let num_lock = Output::Led {
+            device: id,
+            led: Led::NumLock,
+            on: true,
+        };
+        assert_eq!(server.next_local().await, num_lock);
+
+        // The client hasn't turned anything on.
+        server.tap(id, Key::ScrollLock);
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::ScrollLock, Direction::Up))
+        );
+        for led in [Led::CapsLock, Led::NumLock].iter().copied() {
+            assert_eq!(
+                server.next_local().await,
+                Output::Led {
+                    device: id,
+                    led,
+                    on: false,
+                }
+            );
+        }
+
+        // The client's Caps Lock is shown while it's active.
+        server.leds.send((0, id, Led::CapsLock, true)).unwrap();
+        assert_eq!(
+            server.next_local().await,
+            Output::Led {
+                device: id,
+                led: Led::CapsLock,
+                on: true,
+            }
+        );
+
+        // Switching back restores the LEDs of this machine, including the ones it never turned on.
+        server.tap(id, Key::ScrollLock);
+        assert_eq!(
+            server.next_local().await,
+            Output::Led {
+                device: id,
+                led: Led::CapsLock,
+                on: false,
+            }
+        );
+        assert_eq!(server.next_local().await, num_lock);
+    }
+
+    #[tokio::test]
+    async fn keys_are_remapped_per_client() {
+        let server = Server::start("[clients.\"127.0.0.1\".remap]\nA = \"B\"");
+        let mut client = server.connect(0);
+        let id = server.add_keyboard();
+        wait_for_device(&mut client).await;
+
+        server.tap(id, Key::ScrollLock);
+        server.tap(id, Key::A);
+
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::ScrollLock, Direction::Up))
+        );
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::B, Direction::Down))
+        );
+        assert_eq!(
+            next_event(&mut client).await,
+            (Some(id), key_event(Key::B, Direction::Up))
+        );
+    }
+}
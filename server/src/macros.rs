@@ -0,0 +1,198 @@
+use input::{Direction, Event, KeyKind, ParseKeyError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time;
+
+pub type Macros = HashMap<KeyKind, Arc<Vec<Step>>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    Event(Event),
+    Delay(Duration),
+}
+
+pub struct MacroEngine {
+    macros: Macros,
+    sender: UnboundedSender<Event>,
+    receiver: UnboundedReceiver<Event>,
+}
+
+impl MacroEngine {
+    pub fn new(macros: Macros) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            macros,
+            sender,
+            receiver,
+        }
+    }
+
+    // Starts the macro bound to the key, if any. Returns whether the event was consumed.
+    pub fn handle(&mut self, event: Event) -> bool {
+        let (direction, kind) = match event {
+            Event::Key { direction, kind } => (direction, kind),
+            _ => return false,
+        };
+
+        let steps = match self.macros.get(&kind) {
+            Some(steps) => steps.clone(),
+            None => return false,
+        };
+
+        // The macro runs on press, the release is swallowed as well.
+        if direction == Direction::Down {
+            let sender = self.sender.clone();
+            tokio::spawn(async move {
+                for step in steps.iter() {
+                    match step {
+                        Step::Event(event) => {
+                            if sender.send(*event).is_err() {
+                                return;
+                            }
+                        }
+                        Step::Delay(delay) => time::sleep(*delay).await,
+                    }
+                }
+            });
+        }
+
+        true
+    }
+
+    // Returns the next event emitted by a running macro.
+    pub async fn read(&mut self) -> Event {
+        // We're holding a sender ourselves, so the channel can never be closed.
+        self.receiver.recv().await.unwrap()
+    }
+}
+
+// Parses steps of a macro, which are either chords to tap ("Ctrl+C"), keys to press ("+LeftShift") or release
+// ("-LeftShift"), or delays ("50ms").
+pub fn parse(steps: &[String]) -> Result<Vec<Step>, String> {
+    let mut parsed = Vec::new();
+    for step in steps {
+        let step = step.trim();
+
+        if let Some(delay) = step.strip_suffix("ms") {
+            if let Ok(delay) = delay.trim().parse() {
+                parsed.push(Step::Delay(Duration::from_millis(delay)));
+                continue;
+            }
+        }
+
+        let (name, direction) = match (step.strip_prefix('+'), step.strip_prefix('-')) {
+            (Some(name), _) => (name, Direction::Down),
+            (_, Some(name)) => (name, Direction::Up),
+            _ => {
+                let chord = input::parse_chord(step).map_err(|err| err.to_string())?;
+                parsed.extend(chord.iter().map(|kind| key(*kind, Direction::Down)));
+                parsed.extend(chord.iter().rev().map(|kind| key(*kind, Direction::Up)));
+                continue;
+            }
+        };
+
+        let kind = name
+            .trim()
+            .parse()
+            .map_err(|err: ParseKeyError| err.to_string())?;
+        parsed.push(key(kind, direction));
+    }
+
+    Ok(parsed)
+}
+
+fn key(kind: KeyKind, direction: Direction) -> Step {
+    Step::Event(Event::Key { direction, kind })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{Axis, Key};
+
+    fn steps(steps: &[&str]) -> Result<Vec<Step>, String> {
+        parse(
+            &steps
+                .iter()
+                .map(|step| step.to_string())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn step(key: Key, direction: Direction) -> Step {
+        self::key(KeyKind::Key(key), direction)
+    }
+
+    #[test]
+    fn chords_are_tapped_in_order() {
+        assert_eq!(
+            steps(&["Ctrl+C"]).unwrap(),
+            vec![
+                step(Key::LeftCtrl, Direction::Down),
+                step(Key::C, Direction::Down),
+                step(Key::C, Direction::Up),
+                step(Key::LeftCtrl, Direction::Up),
+            ]
+        );
+    }
+
+    #[test]
+    fn presses_releases_and_delays() {
+        assert_eq!(
+            steps(&["+LeftShift", " 50ms ", "-LeftShift"]).unwrap(),
+            vec![
+                step(Key::LeftShift, Direction::Down),
+                Step::Delay(Duration::from_millis(50)),
+                step(Key::LeftShift, Direction::Up),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_errors() {
+        assert!(steps(&["Ctrl+Nope"]).is_err());
+        assert!(steps(&["+Nope"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn triggers_are_swallowed() {
+        let mut macros = Macros::new();
+        macros.insert(KeyKind::Key(Key::F13), Arc::new(steps(&["A"]).unwrap()));
+        let mut engine = MacroEngine::new(macros);
+
+        assert!(engine.handle(Event::Key {
+            direction: Direction::Down,
+            kind: KeyKind::Key(Key::F13),
+        }));
+        assert!(engine.handle(Event::Key {
+            direction: Direction::Up,
+            kind: KeyKind::Key(Key::F13),
+        }));
+
+        // Other keys and events pass through.
+        assert!(!engine.handle(Event::Key {
+            direction: Direction::Down,
+            kind: KeyKind::Key(Key::A),
+        }));
+        assert!(!engine.handle(Event::MouseMove {
+            axis: Axis::X,
+            delta: 1,
+        }));
+
+        // Only the press played the macro.
+        for direction in [Direction::Down, Direction::Up].iter() {
+            assert_eq!(
+                engine.read().await,
+                Event::Key {
+                    direction: *direction,
+                    kind: KeyKind::Key(Key::A),
+                }
+            );
+        }
+        assert!(time::timeout(Duration::from_millis(50), engine.read())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,372 @@
+use input::{Axis, DeviceId, Event};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PointerConfig {
+    // Multiplier applied to all pointer movement.
+    #[serde(default = "one", deserialize_with = "deserialize_positive")]
+    pub sensitivity: f64,
+    #[serde(default)]
+    pub acceleration: Option<Acceleration>,
+    // Multiplier applied to the scroll wheel, separately from movement.
+    #[serde(default = "one", deserialize_with = "deserialize_positive")]
+    pub scroll_sensitivity: f64,
+    // Inverts the scroll direction.
+    #[serde(default)]
+    pub natural_scroll: bool,
+}
+
+impl Default for PointerConfig {
+    fn default() -> Self {
+        Self {
+            sensitivity: 1.0,
+            acceleration: None,
+            scroll_sensitivity: 1.0,
+            natural_scroll: false,
+        }
+    }
+}
+
+// Movements faster than the threshold get an extra multiplier, which grows by the factor for every unit over the
+// threshold up to the limit.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Acceleration {
+    #[serde(deserialize_with = "deserialize_non_negative")]
+    pub threshold: f64,
+    #[serde(deserialize_with = "deserialize_non_negative")]
+    pub factor: f64,
+    #[serde(default = "unlimited", deserialize_with = "deserialize_limit")]
+    pub limit: f64,
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+fn unlimited() -> f64 {
+    f64::INFINITY
+}
+
+fn deserialize_positive<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(D::Error::custom(format!("must be positive, got {}", value)))
+    }
+}
+
+fn deserialize_non_negative<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(D::Error::custom(format!(
+            "must not be negative, got {}",
+            value
+        )))
+    }
+}
+
+// Unlike the others, the limit can be infinite.
+fn deserialize_limit<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    if value >= 1.0 {
+        Ok(value)
+    } else {
+        Err(D::Error::custom(format!(
+            "must be at least 1, got {}",
+            value
+        )))
+    }
+}
+
+pub struct Pointer {
+    config: PointerConfig,
+    // Fractions of a unit left over from previous events, so that slow movements add up instead of being rounded away.
+    remainder_x: f64,
+    remainder_y: f64,
+    remainder_scroll: f64,
+    // Movement of the current frame of each device, which is scaled as a whole once the frame ends. The speed is then
+    // that of the pointer rather than of each axis, so diagonal movement keeps its direction.
+    frames: HashMap<DeviceId, (i32, i32)>,
+}
+
+impl Pointer {
+    pub fn new(config: PointerConfig) -> Self {
+        Self {
+            config,
+            remainder_x: 0.0,
+            remainder_y: 0.0,
+            remainder_scroll: 0.0,
+            frames: HashMap::new(),
+        }
+    }
+
+    // Scales relative movement and scrolling of the device. Movement is held back until the end of its frame,
+    // anything scaled down to nothing (yet) is left out.
+    pub fn scale(&mut self, id: DeviceId, event: Event) -> Vec<Event> {
+        match event {
+            Event::MouseMove { axis, delta } => {
+                let (x, y) = self.frames.entry(id).or_insert((0, 0));
+                match axis {
+                    Axis::X => *x += delta,
+                    Axis::Y => *y += delta,
+                }
+
+                Vec::new()
+            }
+            Event::Sync => match self.frames.remove(&id) {
+                Some((x, y)) => {
+                    let mut events = self.movement(x, y);
+                    events.push(Event::Sync);
+                    events
+                }
+                None => vec![Event::Sync],
+            },
+            Event::MouseScroll { delta } => {
+                let mut scaled = f64::from(delta) * self.config.scroll_sensitivity;
+                if self.config.natural_scroll {
+                    scaled = -scaled;
+                }
+
+                accumulate(&mut self.remainder_scroll, scaled)
+                    .map(|delta| Event::MouseScroll { delta })
+                    .into_iter()
+                    .collect()
+            }
+            event => vec![event],
+        }
+    }
+
+    fn movement(&mut self, x: i32, y: i32) -> Vec<Event> {
+        let (x, y) = (f64::from(x), f64::from(y));
+        let gain = self.config.sensitivity * self.acceleration(x.hypot(y));
+
+        let mut events = Vec::new();
+        for (axis, delta, remainder) in [
+            (Axis::X, x, &mut self.remainder_x),
+            (Axis::Y, y, &mut self.remainder_y),
+        ]
+        .iter_mut()
+        {
+            // Leave the remainder of an axis that didn't move alone.
+            if *delta == 0.0 {
+                continue;
+            }
+
+            if let Some(delta) = accumulate(remainder, *delta * gain) {
+                events.push(Event::MouseMove { axis: *axis, delta });
+            }
+        }
+
+        events
+    }
+
+    fn acceleration(&self, speed: f64) -> f64 {
+        match &self.config.acceleration {
+            Some(acceleration) if speed > acceleration.threshold => {
+                let gain = 1.0 + (speed - acceleration.threshold) * acceleration.factor;
+                gain.min(acceleration.limit).max(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+// Adds the value to the remainder and takes out the whole part of it.
+fn accumulate(remainder: &mut f64, value: f64) -> Option<i32> {
+    // Don't let leftovers from moving one way slow down moving back the other way.
+    if value.signum() != remainder.signum() {
+        *remainder = 0.0;
+    }
+
+    *remainder += value;
+    let whole = remainder.trunc();
+    *remainder -= whole;
+
+    if whole == 0.0 {
+        return None;
+    }
+
+    Some(whole as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::mock;
+
+    fn pointer(config: &str) -> Pointer {
+        Pointer::new(toml::from_str(config).unwrap())
+    }
+
+    fn frame(pointer: &mut Pointer, id: DeviceId, x: i32, y: i32) -> Vec<Event> {
+        pointer.scale(
+            id,
+            Event::MouseMove {
+                axis: Axis::X,
+                delta: x,
+            },
+        );
+        pointer.scale(
+            id,
+            Event::MouseMove {
+                axis: Axis::Y,
+                delta: y,
+            },
+        );
+        pointer.scale(id, Event::Sync)
+    }
+
+    fn moved(x: i32, y: i32) -> Vec<Event> {
+        vec![
+            Event::MouseMove {
+                axis: Axis::X,
+                delta: x,
+            },
+            Event::MouseMove {
+                axis: Axis::Y,
+                delta: y,
+            },
+            Event::Sync,
+        ]
+    }
+
+    #[test]
+    fn fractions_add_up() {
+        let id = mock::device_id();
+        let mut pointer = pointer("sensitivity = 0.5");
+
+        assert_eq!(frame(&mut pointer, id, 1, 0), vec![Event::Sync]);
+        assert_eq!(
+            frame(&mut pointer, id, 1, 0),
+            vec![
+                Event::MouseMove {
+                    axis: Axis::X,
+                    delta: 1
+                },
+                Event::Sync
+            ]
+        );
+        assert_eq!(frame(&mut pointer, id, 3, -3), moved(1, -1));
+        assert_eq!(frame(&mut pointer, id, 1, -1), moved(1, -1));
+    }
+
+    #[test]
+    fn acceleration_keeps_the_direction() {
+        let id = mock::device_id();
+        let mut pointer = pointer("acceleration = { threshold = 2, factor = 1 }");
+
+        // The speed is 5, so both axes get a gain of 4.
+        assert_eq!(frame(&mut pointer, id, 3, 4), moved(12, 16));
+        assert_eq!(frame(&mut pointer, id, 1, 1), moved(1, 1));
+    }
+
+    #[test]
+    fn frames_end_with_their_own_device() {
+        let mouse = mock::device_id();
+        let tablet = mock::device_id();
+        let mut pointer = pointer("");
+
+        pointer.scale(
+            mouse,
+            Event::MouseMove {
+                axis: Axis::X,
+                delta: 2,
+            },
+        );
+        assert_eq!(pointer.scale(tablet, Event::Sync), vec![Event::Sync]);
+        assert_eq!(
+            pointer.scale(mouse, Event::Sync),
+            vec![
+                Event::MouseMove {
+                    axis: Axis::X,
+                    delta: 2
+                },
+                Event::Sync
+            ]
+        );
+    }
+
+    #[test]
+    fn interleaved_devices_keep_their_frames() {
+        let first = mock::device_id();
+        let second = mock::device_id();
+        let mut pointer = pointer("");
+
+        for (id, delta) in [(first, 2), (second, 5)].iter().copied() {
+            pointer.scale(
+                id,
+                Event::MouseMove {
+                    axis: Axis::X,
+                    delta,
+                },
+            );
+        }
+
+        assert_eq!(
+            pointer.scale(second, Event::Sync),
+            vec![
+                Event::MouseMove {
+                    axis: Axis::X,
+                    delta: 5
+                },
+                Event::Sync
+            ]
+        );
+        assert_eq!(
+            pointer.scale(first, Event::Sync),
+            vec![
+                Event::MouseMove {
+                    axis: Axis::X,
+                    delta: 2
+                },
+                Event::Sync
+            ]
+        );
+    }
+
+    #[test]
+    fn sensitivity_has_to_be_positive() {
+        let parses = |config| toml::from_str::<PointerConfig>(config).is_ok();
+
+        assert!(!parses("sensitivity = 0.0"));
+        assert!(!parses("sensitivity = -1.0"));
+        assert!(!parses("sensitivity = nan"));
+        assert!(parses("sensitivity = 2.0"));
+
+        assert!(!parses("scroll-sensitivity = 0.0"));
+        assert!(!parses("scroll-sensitivity = -1.0"));
+        assert!(parses("scroll-sensitivity = 0.5"));
+
+        assert!(!parses("acceleration = { threshold = -1, factor = 1 }"));
+        assert!(!parses("acceleration = { threshold = 2, factor = -1 }"));
+        assert!(!parses("acceleration = { threshold = 2, factor = nan }"));
+        assert!(!parses(
+            "acceleration = { threshold = 2, factor = 1, limit = 0.5 }"
+        ));
+        assert!(!parses(
+            "acceleration = { threshold = 2, factor = 1, limit = nan }"
+        ));
+        assert!(parses(
+            "acceleration = { threshold = 0, factor = 0, limit = 1 }"
+        ));
+        assert!(parses(
+            "acceleration = { threshold = 2, factor = 1, limit = inf }"
+        ));
+    }
+}
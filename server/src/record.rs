@@ -0,0 +1,31 @@
+use anyhow::{Context, Error};
+use input::{DeviceEvent, EventMonitor};
+use net::record::{Format, Record, Recorder};
+use std::convert::Infallible;
+use std::path::Path;
+use tokio::fs::File;
+
+// Records events from all devices into a file, without grabbing them.
+pub async fn run(path: &Path) -> Result<Infallible, Error> {
+    let file = File::create(path)
+        .await
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut recorder = Recorder::new(file, Format::from_path(path)).await?;
+    let mut monitor = EventMonitor::new().await?;
+
+    log::info!("Recording to {}, Ctrl+C to stop", path.display());
+
+    loop {
+        let record = match monitor.read().await? {
+            DeviceEvent::Added { id, info } => Record::DeviceAdded { id, info },
+            DeviceEvent::Removed { id } => Record::DeviceRemoved { id },
+            DeviceEvent::Event { id, event } => Record::Event {
+                device: Some(id),
+                event,
+            },
+            DeviceEvent::Led { .. } => continue,
+        };
+
+        recorder.write(record).await?;
+    }
+}
@@ -0,0 +1,250 @@
+use input::{DeviceId, DeviceInfo, Direction, Event, KeyKind};
+use std::collections::HashMap;
+
+// Keys mapped to nothing are disabled, keys mapped to multiple keys are pressed as a chord.
+pub type Table = HashMap<KeyKind, Vec<KeyKind>>;
+
+// Where a key is sent to, either the client's mirror of the source device or its generic device.
+type Target = (Option<DeviceId>, KeyKind);
+
+pub struct Remapper {
+    table: Table,
+    // What each held key was turned into when it was pressed, so that releasing it releases the same keys,
+    // even if the table has changed since.
+    pressed: HashMap<(DeviceId, KeyKind), Vec<Target>>,
+    // How many held keys map to each target, which is only released once none of them are held.
+    held: HashMap<Target, usize>,
+}
+
+impl Remapper {
+    pub fn new(table: Table) -> Self {
+        Self {
+            table,
+            pressed: HashMap::new(),
+            held: HashMap::new(),
+        }
+    }
+
+    pub fn remap(
+        &mut self,
+        id: DeviceId,
+        info: &DeviceInfo,
+        event: Event,
+    ) -> Vec<(Option<DeviceId>, Event)> {
+        let (direction, kind) = match event {
+            Event::Key { direction, kind } => (direction, kind),
+            event => return vec![(Some(id), event)],
+        };
+
+        match direction {
+            Direction::Down => {
+                // We've already seen the press, the target keys are already down.
+                if self.pressed.contains_key(&(id, kind)) {
+                    return Vec::new();
+                }
+
+                let targets = self
+                    .table
+                    .get(&kind)
+                    .map(Vec::as_slice)
+                    .unwrap_or(&[kind])
+                    .iter()
+                    .map(|target| (route(id, info, *target), *target))
+                    .collect::<Vec<_>>();
+
+                let mut events = Vec::new();
+                for target in &targets {
+                    if self.press(*target) {
+                        events.push(key(*target, Direction::Down));
+                    }
+                }
+
+                self.pressed.insert((id, kind), targets);
+                events
+            }
+            Direction::Up => match self.pressed.remove(&(id, kind)) {
+                Some(targets) => self.release(&targets),
+                // The key was pressed before we started tracking it, so just pass the release through.
+                None => vec![key((route(id, info, kind), kind), Direction::Up)],
+            },
+        }
+    }
+
+    // Releases all held keys, used when the client stops being the active one.
+    pub fn release_all(&mut self) -> Vec<(Option<DeviceId>, Event)> {
+        let pressed = self.pressed.drain().collect::<Vec<_>>();
+        pressed
+            .into_iter()
+            .flat_map(|(_, targets)| self.release(&targets))
+            .collect()
+    }
+
+    // Forgets keys held on a removed device. Keys that went to the device's mirror are released along with it,
+    // so only the ones that went elsewhere need to be released.
+    pub fn remove_device(&mut self, id: DeviceId) -> Vec<(Option<DeviceId>, Event)> {
+        let keys = self
+            .pressed
+            .keys()
+            .filter(|(device, _)| *device == id)
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut events = Vec::new();
+        for key in keys {
+            let targets = self.pressed.remove(&key).unwrap();
+            events.extend(self.release(&targets));
+        }
+
+        events.retain(|(device, _)| *device != Some(id));
+        events
+    }
+
+    fn press(&mut self, target: Target) -> bool {
+        let count = self.held.entry(target).or_insert(0);
+        *count += 1;
+
+        *count == 1
+    }
+
+    fn release(&mut self, targets: &[Target]) -> Vec<(Option<DeviceId>, Event)> {
+        let mut events = Vec::new();
+
+        // Release chords in reverse order, so that modifiers go up last.
+        for target in targets.iter().rev() {
+            let count = self.held.get_mut(target).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                self.held.remove(target);
+                events.push(key(*target, Direction::Up));
+            }
+        }
+
+        events
+    }
+}
+
+// Remapped keys might not exist on the source device, in which case they go to the generic device.
+fn route(id: DeviceId, info: &DeviceInfo, kind: KeyKind) -> Option<DeviceId> {
+    if info.keys.contains(&kind) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+fn key((device, kind): Target, direction: Direction) -> (Option<DeviceId>, Event) {
+    (device, Event::Key { direction, kind })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::mock;
+    use input::Key;
+
+    fn keyboard() -> DeviceInfo {
+        DeviceInfo {
+            keys: [Key::A, Key::B, Key::LeftCtrl]
+                .iter()
+                .map(|key| KeyKind::Key(*key))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn table(entries: &[(Key, &[Key])]) -> Table {
+        entries
+            .iter()
+            .map(|(from, to)| {
+                let to = to.iter().map(|key| KeyKind::Key(*key)).collect();
+                (KeyKind::Key(*from), to)
+            })
+            .collect()
+    }
+
+    fn event(key: Key, direction: Direction) -> Event {
+        Event::Key {
+            direction,
+            kind: KeyKind::Key(key),
+        }
+    }
+
+    #[test]
+    fn keys_are_released_where_they_were_pressed() {
+        let id = mock::device_id();
+        let info = keyboard();
+        let mut remapper = Remapper::new(table(&[(Key::A, &[Key::B])]));
+
+        assert_eq!(
+            remapper.remap(id, &info, event(Key::A, Direction::Down)),
+            vec![(Some(id), event(Key::B, Direction::Down))]
+        );
+
+        // The device has no C, which would go to the generic device, but B is what's held.
+        remapper.table = table(&[(Key::A, &[Key::C])]);
+        assert_eq!(
+            remapper.remap(id, &info, event(Key::A, Direction::Up)),
+            vec![(Some(id), event(Key::B, Direction::Up))]
+        );
+    }
+
+    #[test]
+    fn keys_missing_from_the_device_go_to_the_generic_device() {
+        let id = mock::device_id();
+        let mut remapper = Remapper::new(table(&[(Key::A, &[Key::C])]));
+
+        assert_eq!(
+            remapper.remap(id, &keyboard(), event(Key::A, Direction::Down)),
+            vec![(None, event(Key::C, Direction::Down))]
+        );
+    }
+
+    #[test]
+    fn shared_targets_are_released_with_the_last_key() {
+        let id = mock::device_id();
+        let info = keyboard();
+        let mut remapper = Remapper::new(table(&[
+            (Key::A, &[Key::LeftCtrl, Key::B]),
+            (Key::B, &[Key::LeftCtrl]),
+        ]));
+
+        assert_eq!(
+            remapper.remap(id, &info, event(Key::A, Direction::Down)),
+            vec![
+                (Some(id), event(Key::LeftCtrl, Direction::Down)),
+                (Some(id), event(Key::B, Direction::Down)),
+            ]
+        );
+        assert_eq!(
+            remapper.remap(id, &info, event(Key::B, Direction::Down)),
+            vec![]
+        );
+
+        // Chords are released in reverse, Ctrl is still held by B.
+        assert_eq!(
+            remapper.remap(id, &info, event(Key::A, Direction::Up)),
+            vec![(Some(id), event(Key::B, Direction::Up))]
+        );
+        assert_eq!(
+            remapper.release_all(),
+            vec![(Some(id), event(Key::LeftCtrl, Direction::Up))]
+        );
+    }
+
+    #[test]
+    fn removed_devices_only_release_keys_sent_elsewhere() {
+        let id = mock::device_id();
+        let info = keyboard();
+        let mut remapper = Remapper::new(table(&[(Key::A, &[Key::C])]));
+
+        remapper.remap(id, &info, event(Key::A, Direction::Down));
+        remapper.remap(id, &info, event(Key::B, Direction::Down));
+
+        assert_eq!(
+            remapper.remove_device(id),
+            vec![(None, event(Key::C, Direction::Up))]
+        );
+        assert_eq!(remapper.release_all(), vec![]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::config::Config;
+use anyhow::{Context, Error};
+use input::EventWriter;
+use net::record::{Format, Player, Record};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+// Plays a recording back on this machine, recreating the recorded devices.
+pub async fn local(path: &Path, speed: f64) -> Result<(), Error> {
+    let mut player = open(path, speed).await?;
+    let mut writer = EventWriter::new().await?;
+    while let Some(record) = player.read().await? {
+        match record {
+            Record::DeviceAdded { id, info } => writer.add_device(id, info).await?,
+            Record::DeviceRemoved { id } => writer.remove_device(id).await?,
+            Record::Event {
+                device: Some(id),
+                event,
+            } => writer.write_device(id, event).await?,
+            Record::Event {
+                device: None,
+                event,
+            } => writer.write(event).await?,
+        }
+    }
+
+    Ok(())
+}
+
+// Sends a recording to a client once it connects. The client connects to the listen address from the config unless
+// another one is given, which is needed while the server itself is running.
+pub async fn to_client(
+    config: &Config,
+    path: &Path,
+    speed: f64,
+    address: IpAddr,
+    listen_address: Option<SocketAddr>,
+) -> Result<(), Error> {
+    let mut player = open(path, speed).await?;
+
+    let acceptor = crate::tls_acceptor(config).await?;
+    let listen_address = listen_address.unwrap_or(config.listen_address);
+    let listener = TcpListener::bind(listen_address)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen_address))?;
+
+    log::info!("Waiting for {} to connect to {}", address, listen_address);
+
+    let stream = loop {
+        let (stream, peer) = listener.accept().await?;
+        if peer.ip() != address {
+            continue;
+        }
+
+        match acceptor.accept(stream).await {
+            Ok(stream) => break stream,
+            Err(err) => log::error!("{}: TLS error: {}", peer, err),
+        }
+    };
+
+    log::info!("{}: connected, replaying", address);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    // The client's LED changes of the replayed devices aren't of any use.
+    let (led_sender, _led_receiver) = mpsc::unbounded_channel();
+    let connection = tokio::spawn(crate::handle_connection(stream, receiver, 0, led_sender));
+
+    while let Some(record) = player.read().await? {
+        if sender.send(record.into()).is_err() {
+            break;
+        }
+    }
+
+    // Closing the channel lets the connection finish writing and exit.
+    drop(sender);
+    connection.await?
+}
+
+async fn open(path: &Path, speed: f64) -> Result<Player<File>, Error> {
+    let file = File::open(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    Player::new(file, Format::from_path(path), speed)
+        .await
+        .context("Failed to read recording")
+}
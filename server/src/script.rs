@@ -0,0 +1,389 @@
+use anyhow::{Context, Error};
+use input::{AbsAxis, Axis, DeviceInfo, Direction, Event, ParseKeyError};
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::fs;
+
+// Name of the function in the script that's called for every event.
+const HOOK: &str = "event";
+
+// A user script that can drop, rewrite or add events and pick the client they go to.
+//
+// The script defines `fn event(event, context)`, where `event` is a map such as
+// `#{ type: "key", key: "LeftCtrl", down: true }` and `context` holds the name of the device it came from, the index
+// of the active client and the number of clients.
+// Returning nothing passes the event on unchanged, otherwise the script returns an event or an array of them,
+// each of which can have a `client` field to send it elsewhere than the active client (0 is the server).
+// Functions can keep state in `this`, which is kept for as long as the server runs.
+pub struct Script {
+    path: PathBuf,
+    engine: Engine,
+    ast: Option<AST>,
+    modified: Option<SystemTime>,
+    state: Dynamic,
+}
+
+impl Script {
+    pub async fn load(path: &Path) -> Result<Self, Error> {
+        let mut engine = Engine::new();
+        // Keep scripts stuck in a loop from freezing all input.
+        engine.set_max_operations(100_000);
+        engine.on_print(|message| log::info!("Script: {}", message));
+
+        let mut script = Self {
+            path: path.to_owned(),
+            engine,
+            ast: None,
+            modified: None,
+            state: Dynamic::from(Map::new()),
+        };
+
+        script.reload().await?;
+        Ok(script)
+    }
+
+    // Reloads the script if it has been modified since it was last loaded. A script that fails to load is reported
+    // and the previous version is kept running.
+    pub async fn reload_if_modified(&mut self) {
+        let modified = fs::metadata(&self.path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        if modified.is_none() || modified == self.modified {
+            return;
+        }
+
+        match self.reload().await {
+            Ok(()) => log::info!("Reloaded script {}", self.path.display()),
+            Err(err) => log::error!("Error reloading script: {:#}", err),
+        }
+    }
+
+    async fn reload(&mut self) -> Result<(), Error> {
+        let modified = fs::metadata(&self.path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        // Don't retry a broken script until it changes again.
+        self.modified = modified;
+
+        let source = fs::read_to_string(&self.path)
+            .await
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let ast = self
+            .engine
+            .compile(&source)
+            .map_err(|err| anyhow::anyhow!("{}: {}", self.path.display(), err))?;
+
+        // Otherwise every event would fail to call it and log the same error.
+        if !ast
+            .iter_functions()
+            .any(|function| function.name == HOOK && function.params.len() == 2)
+        {
+            anyhow::bail!(
+                "{}: missing fn {}(event, context)",
+                self.path.display(),
+                HOOK
+            );
+        }
+
+        self.ast = Some(ast);
+        Ok(())
+    }
+
+    // Runs the event through the script, returning the events to send along with the index of the client they should
+    // go to. Errors in the script are logged and the event is passed on unchanged.
+    pub fn run(
+        &mut self,
+        info: Option<&DeviceInfo>,
+        event: Event,
+        current: usize,
+        clients: usize,
+    ) -> Vec<(usize, Event)> {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return vec![(current, event)],
+        };
+
+        let mut context = Map::new();
+        context.insert(
+            "device".into(),
+            Dynamic::from(info.map(|info| info.name.clone()).unwrap_or_default()),
+        );
+        context.insert("client".into(), Dynamic::from(current as i64));
+        context.insert("clients".into(), Dynamic::from(clients as i64));
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            ast,
+            HOOK,
+            (to_map(event), context),
+        );
+
+        let result = match result {
+            Ok(result) if result.is_unit() => return vec![(current, event)],
+            Ok(result) => from_result(result, current, clients),
+            Err(err) => Err(err.to_string()),
+        };
+
+        match result {
+            Ok(events) => events,
+            Err(err) => {
+                log::error!("Script error: {}", err);
+                vec![(current, event)]
+            }
+        }
+    }
+}
+
+fn from_result(
+    result: Dynamic,
+    current: usize,
+    clients: usize,
+) -> Result<Vec<(usize, Event)>, String> {
+    let results = if result.is_array() {
+        result.into_array()?
+    } else {
+        vec![result]
+    };
+
+    let mut events = Vec::new();
+    for result in results {
+        let map = result
+            .try_cast::<Map>()
+            .ok_or_else(|| "expected an event map".to_owned())?;
+
+        let client = match map.get("client") {
+            Some(client) => {
+                let client = client
+                    .as_int()
+                    .map_err(|_| "client must be an integer".to_owned())?;
+                if client < 0 || client as usize > clients {
+                    return Err(format!("no such client {}", client));
+                }
+
+                client as usize
+            }
+            None => current,
+        };
+
+        events.push((client, from_map(&map)?));
+    }
+
+    Ok(events)
+}
+
+fn to_map(event: Event) -> Map {
+    let mut map = Map::new();
+    let mut insert = |name: &str, value: Dynamic| {
+        map.insert(name.into(), value);
+    };
+
+    match event {
+        Event::Key { direction, kind } => {
+            insert("type", "key".into());
+            insert("key", kind.to_string().into());
+            insert("down", (direction == Direction::Down).into());
+        }
+        Event::MouseMove { axis, delta } => {
+            insert("type", "move".into());
+            insert("axis", format!("{:?}", axis).into());
+            insert("delta", (delta as i64).into());
+        }
+        Event::MouseScroll { delta } => {
+            insert("type", "scroll".into());
+            insert("delta", (delta as i64).into());
+        }
+        Event::Absolute { axis, value } => {
+            insert("type", "absolute".into());
+            insert("axis", format!("{:?}", axis).into());
+            insert("value", (value as i64).into());
+        }
+        Event::Sync => insert("type", "sync".into()),
+    }
+
+    map
+}
+
+fn from_map(map: &Map) -> Result<Event, String> {
+    let event = match get_string(map, "type")?.as_str() {
+        "key" => {
+            let kind = get_string(map, "key")?
+                .parse()
+                .map_err(|err: ParseKeyError| err.to_string())?;
+            let down = map
+                .get("down")
+                .and_then(|down| down.as_bool().ok())
+                .ok_or_else(|| "key events need a \"down\" boolean".to_owned())?;
+            let direction = if down { Direction::Down } else { Direction::Up };
+
+            Event::Key { direction, kind }
+        }
+        "move" => Event::MouseMove {
+            axis: from_name::<Axis>(&get_string(map, "axis")?)?,
+            delta: get_int(map, "delta")?,
+        },
+        "scroll" => Event::MouseScroll {
+            delta: get_int(map, "delta")?,
+        },
+        "absolute" => Event::Absolute {
+            axis: from_name::<AbsAxis>(&get_string(map, "axis")?)?,
+            value: get_int(map, "value")?,
+        },
+        "sync" => Event::Sync,
+        kind => return Err(format!("unknown event type \"{}\"", kind)),
+    };
+
+    Ok(event)
+}
+
+fn get_string(map: &Map, name: &str) -> Result<String, String> {
+    map.get(name)
+        .and_then(|value| value.clone().into_string().ok())
+        .ok_or_else(|| format!("missing string \"{}\"", name))
+}
+
+fn get_int(map: &Map, name: &str) -> Result<i32, String> {
+    let value = map
+        .get(name)
+        .and_then(|value| value.as_int().ok())
+        .ok_or_else(|| format!("missing integer \"{}\"", name))?;
+
+    i32::try_from(value).map_err(|_| format!("\"{}\" out of range", name))
+}
+
+// Axes are named after their variants, which serde can parse for us.
+fn from_name<T: serde::de::DeserializeOwned>(name: &str) -> Result<T, String> {
+    rhai::serde::from_dynamic(&Dynamic::from(name.to_owned()))
+        .map_err(|_| format!("unknown axis \"{}\"", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{Key, KeyKind};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn key(key: Key, direction: Direction) -> Event {
+        Event::Key {
+            direction,
+            kind: KeyKind::Key(key),
+        }
+    }
+
+    async fn load(source: &str) -> (Script, NamedTempFile) {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(source.as_bytes()).unwrap();
+
+        (Script::load(file.path()).await.unwrap(), file)
+    }
+
+    #[test]
+    fn events_survive_conversion() {
+        let events = [
+            key(Key::LeftCtrl, Direction::Down),
+            Event::Key {
+                direction: Direction::Up,
+                kind: KeyKind::Button(input::Button::Left),
+            },
+            Event::MouseMove {
+                axis: Axis::Y,
+                delta: -3,
+            },
+            Event::MouseScroll { delta: 1 },
+            Event::Absolute {
+                axis: AbsAxis::MtPositionX,
+                value: 500,
+            },
+            Event::Sync,
+        ];
+
+        for event in events.iter() {
+            assert_eq!(from_map(&to_map(*event)), Ok(*event));
+        }
+    }
+
+    #[test]
+    fn malformed_events_are_errors() {
+        let mut map = to_map(key(Key::A, Direction::Down));
+        map.insert("key".into(), "Nope".into());
+        assert!(from_map(&map).is_err());
+
+        map.insert("type".into(), "teleport".into());
+        assert!(from_map(&map).is_err());
+
+        let mut map = to_map(Event::MouseScroll { delta: 1 });
+        map.insert("delta".into(), Dynamic::from(5_000_000_000i64));
+        assert_eq!(from_map(&map), Err("\"delta\" out of range".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn scripts_need_the_hook() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"fn events(event) { event }").unwrap();
+
+        let err = Script::load(file.path()).await.err().unwrap();
+        assert!(err.to_string().contains("missing fn event"), "{:#}", err);
+    }
+
+    #[tokio::test]
+    async fn events_are_rewritten_and_redirected() {
+        let (mut script, _file) = load(
+            r#"
+            fn event(event, context) {
+                if event.type == "key" && event.key == "A" {
+                    event.key = "B";
+                    let copy = event;
+                    copy.client = 0;
+                    return [event, copy];
+                }
+            }
+            "#,
+        )
+        .await;
+
+        assert_eq!(
+            script.run(None, key(Key::A, Direction::Down), 1, 1),
+            vec![
+                (1, key(Key::B, Direction::Down)),
+                (0, key(Key::B, Direction::Down)),
+            ]
+        );
+
+        // Returning nothing passes the event on.
+        assert_eq!(
+            script.run(None, key(Key::C, Direction::Down), 1, 1),
+            vec![(1, key(Key::C, Direction::Down))]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_clients_pass_the_event_on() {
+        let (mut script, _file) = load(
+            r#"
+            fn event(event, context) {
+                event.client = context.clients + 1;
+                event
+            }
+            "#,
+        )
+        .await;
+
+        assert_eq!(
+            script.run(None, key(Key::A, Direction::Down), 1, 2),
+            vec![(1, key(Key::A, Direction::Down))]
+        );
+
+        let (mut script, _file) =
+            load("fn event(event, context) { event.client = -1; event }").await;
+        assert_eq!(script.run(None, Event::Sync, 0, 2), vec![(0, Event::Sync)]);
+    }
+}
@@ -0,0 +1,418 @@
+// End-to-end tests running the real server and client logic over TLS on 127.0.0.1, with mock input backends on both
+// sides. Certificates are generated in-process with the same OpenSSL the TLS stack uses.
+
+use anyhow::Error;
+use input::mock::{self, Output, SinkHandle, SourceHandle};
+use input::{DeviceId, DeviceInfo, Direction, Event, Key, KeyKind};
+use net::{MESSAGE_TIMEOUT, PROTOCOL_VERSION};
+use openssl::asn1::Asn1Time;
+use openssl::bn::BigNum;
+use openssl::hash::MessageDigest;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+use openssl::x509::extension::SubjectAlternativeName;
+use openssl::x509::{X509NameBuilder, X509};
+use server::config::Config;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio::time;
+use tokio_native_tls::native_tls::{self, Certificate};
+use tokio_native_tls::{TlsAcceptor, TlsConnector, TlsStream};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const PASSWORD: &str = "test";
+
+struct Identity {
+    // Keeps the files around for the duration of the test.
+    _dir: TempDir,
+    identity_path: PathBuf,
+    certificate_path: PathBuf,
+}
+
+impl Identity {
+    fn generate() -> Self {
+        let dir = TempDir::new().unwrap();
+        let identity_path = dir.path().join("identity.p12");
+        let certificate_path = dir.path().join("certificate.pem");
+
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "rkvm").unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+        builder.set_serial_number(&serial).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let alt_name = SubjectAlternativeName::new()
+            .dns("localhost")
+            .ip("127.0.0.1")
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(alt_name).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        let certificate = builder.build();
+
+        let identity = Pkcs12::builder()
+            .name("rkvm")
+            .pkey(&key)
+            .cert(&certificate)
+            .build2(PASSWORD)
+            .unwrap();
+
+        std::fs::write(&certificate_path, certificate.to_pem().unwrap()).unwrap();
+        std::fs::write(&identity_path, identity.to_der().unwrap()).unwrap();
+
+        Self {
+            _dir: dir,
+            identity_path,
+            certificate_path,
+        }
+    }
+
+    fn config(&self) -> Config {
+        let config = format!(
+            r#"
+            listen-address = "127.0.0.1:0"
+            switch-keys = "ScrollLock"
+            identity-path = {:?}
+            identity-password = {:?}
+            "#,
+            self.identity_path.to_str().unwrap(),
+            PASSWORD
+        );
+
+        toml::from_str(&config).unwrap()
+    }
+
+    async fn acceptor(&self) -> TlsAcceptor {
+        server::tls_acceptor(&self.config()).await.unwrap()
+    }
+
+    async fn connect(&self, port: u16) -> TlsStream<TcpStream> {
+        let certificate = std::fs::read(&self.certificate_path).unwrap();
+        let connector: TlsConnector = native_tls::TlsConnector::builder()
+            .add_root_certificate(Certificate::from_pem(&certificate).unwrap())
+            .build()
+            .unwrap()
+            .into();
+
+        let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        connector.connect("127.0.0.1", stream).await.unwrap()
+    }
+}
+
+struct Server {
+    source: SourceHandle,
+    port: u16,
+    task: JoinHandle<Result<Infallible, Error>>,
+}
+
+impl Server {
+    async fn start(identity: &Identity) -> Self {
+        let config = identity.config();
+        let acceptor = identity.acceptor().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let (source, handle) = mock::source();
+        let task =
+            tokio::spawn(async move { server::serve(&config, listener, acceptor, source).await });
+
+        Self {
+            source: handle,
+            port,
+            task,
+        }
+    }
+
+    fn add_keyboard(&self) -> DeviceId {
+        self.source.add_device(DeviceInfo {
+            name: "Keyboard".to_owned(),
+            keys: [Key::A, Key::B, Key::ScrollLock]
+                .iter()
+                .map(|key| KeyKind::Key(*key))
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    fn key(&self, id: DeviceId, key: Key, direction: Direction) {
+        self.source.event(id, key_event(key, direction));
+    }
+
+    async fn next_local(&mut self) -> Output {
+        time::timeout(TIMEOUT, self.source.next())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct Client {
+    sink: SinkHandle,
+    task: JoinHandle<Result<Infallible, Error>>,
+}
+
+impl Client {
+    fn start(identity: &Identity, port: u16) -> Self {
+        let certificate_path = identity.certificate_path.clone();
+        let (sink, handle) = mock::sink();
+        let task = tokio::spawn(async move {
+            client::run("127.0.0.1", port, &certificate_path, sink, None).await
+        });
+
+        Self { sink: handle, task }
+    }
+
+    async fn next(&mut self) -> Output {
+        time::timeout(TIMEOUT, self.sink.next())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    // Waits for the client to fail and returns its error.
+    async fn error(mut self) -> Error {
+        match time::timeout(TIMEOUT, &mut self.task)
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            Ok(infallible) => match infallible {},
+            Err(err) => err,
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn key_event(key: Key, direction: Direction) -> Event {
+    Event::Key {
+        direction,
+        kind: KeyKind::Key(key),
+    }
+}
+
+fn key_output(id: DeviceId, key: Key, direction: Direction) -> Output {
+    Output::Event {
+        device: Some(id),
+        event: key_event(key, direction),
+    }
+}
+
+// Accepts a single connection and does the server's side of the version handshake with the given version.
+async fn fake_server(identity: &Identity, version: u16) -> (u16, JoinHandle<TlsStream<TcpStream>>) {
+    let acceptor = identity.acceptor().await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let task = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = acceptor.accept(stream).await.unwrap();
+
+        net::write_version(&mut stream, version).await.unwrap();
+        net::read_version(&mut stream).await.unwrap();
+
+        stream
+    });
+
+    (port, task)
+}
+
+async fn handshake<T>(stream: &mut T, version: u16) -> u16
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    net::write_version(&mut *stream, version).await.unwrap();
+    net::read_version(&mut *stream).await.unwrap()
+}
+
+#[tokio::test]
+async fn switching_moves_events_between_server_and_client() {
+    let identity = Identity::generate();
+    let mut server = Server::start(&identity).await;
+    let id = server.add_keyboard();
+
+    let mut client = Client::start(&identity, server.port);
+    assert!(matches!(client.next().await, Output::DeviceAdded { id: added, .. } if added == id));
+
+    // Nothing goes to the client before switching to it.
+    server.key(id, Key::A, Direction::Down);
+    assert_eq!(
+        server.next_local().await,
+        key_output(id, Key::A, Direction::Down)
+    );
+    server.key(id, Key::A, Direction::Up);
+    assert_eq!(
+        server.next_local().await,
+        key_output(id, Key::A, Direction::Up)
+    );
+
+    server.key(id, Key::ScrollLock, Direction::Down);
+    server.key(id, Key::ScrollLock, Direction::Up);
+    server.key(id, Key::B, Direction::Down);
+    assert_eq!(
+        client.next().await,
+        key_output(id, Key::ScrollLock, Direction::Up)
+    );
+    assert_eq!(client.next().await, key_output(id, Key::B, Direction::Down));
+
+    // Switching back releases what's held on the client.
+    server.key(id, Key::ScrollLock, Direction::Down);
+    assert_eq!(client.next().await, key_output(id, Key::B, Direction::Up));
+
+    server.key(id, Key::A, Direction::Down);
+    assert_eq!(
+        server.next_local().await,
+        key_output(id, Key::A, Direction::Down)
+    );
+}
+
+#[tokio::test]
+async fn server_rejects_other_protocol_versions() {
+    let identity = Identity::generate();
+    let server = Server::start(&identity).await;
+    let id = server.add_keyboard();
+
+    let mut stream = identity.connect(server.port).await;
+    assert_eq!(
+        handshake(&mut stream, PROTOCOL_VERSION + 1).await,
+        PROTOCOL_VERSION
+    );
+
+    // The server hangs up instead of sending anything.
+    let result = time::timeout(TIMEOUT, net::read_message(&mut stream))
+        .await
+        .unwrap();
+    assert!(result.is_err());
+
+    // Other clients are unaffected.
+    let mut client = Client::start(&identity, server.port);
+    assert!(matches!(client.next().await, Output::DeviceAdded { id: added, .. } if added == id));
+}
+
+#[tokio::test]
+async fn client_rejects_other_protocol_versions() {
+    let identity = Identity::generate();
+    let (port, _server) = fake_server(&identity, PROTOCOL_VERSION + 1).await;
+
+    let client = Client::start(&identity, port);
+    let err = client.error().await;
+    assert!(
+        err.to_string().contains("Incompatible protocol version"),
+        "{:#}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn keepalives_keep_idle_connections_open() {
+    let identity = Identity::generate();
+    let server = Server::start(&identity).await;
+    let id = server.add_keyboard();
+
+    let mut client = Client::start(&identity, server.port);
+    client.next().await;
+
+    // Nothing but keep alive messages is sent for longer than the timeout.
+    time::sleep(MESSAGE_TIMEOUT + Duration::from_secs(1)).await;
+
+    server.key(id, Key::ScrollLock, Direction::Down);
+    server.key(id, Key::ScrollLock, Direction::Up);
+    assert_eq!(
+        client.next().await,
+        key_output(id, Key::ScrollLock, Direction::Up)
+    );
+}
+
+#[tokio::test]
+async fn client_times_out_when_server_goes_silent() {
+    let identity = Identity::generate();
+    let (port, server) = fake_server(&identity, PROTOCOL_VERSION).await;
+
+    let start = Instant::now();
+    let client = Client::start(&identity, port);
+    // Keep the connection open without sending anything.
+    let _stream = server.await.unwrap();
+
+    let err = client.error().await;
+    assert!(err.to_string().contains("timed out"), "{:#}", err);
+    assert!(start.elapsed() >= MESSAGE_TIMEOUT);
+}
+
+#[tokio::test]
+async fn server_switches_back_when_active_client_disconnects() {
+    let identity = Identity::generate();
+    let mut server = Server::start(&identity).await;
+    let id = server.add_keyboard();
+
+    let mut client = Client::start(&identity, server.port);
+    client.next().await;
+
+    server.key(id, Key::ScrollLock, Direction::Down);
+    server.key(id, Key::ScrollLock, Direction::Up);
+    assert_eq!(
+        client.next().await,
+        key_output(id, Key::ScrollLock, Direction::Up)
+    );
+
+    drop(client);
+
+    // Events keep going to the client until the server notices it's gone, after which they're written locally.
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        assert!(Instant::now() < deadline, "Server kept sending to client");
+
+        server.key(id, Key::A, Direction::Down);
+        server.key(id, Key::A, Direction::Up);
+        time::sleep(Duration::from_millis(100)).await;
+
+        while let Some(output) = server.source.try_next() {
+            if output == key_output(id, Key::A, Direction::Down) {
+                return;
+            }
+
+            // The press of the pair may have still reached the client before it went away.
+            assert_eq!(output, key_output(id, Key::A, Direction::Up));
+        }
+    }
+}
+
+#[tokio::test]
+async fn client_exits_when_server_disconnects() {
+    let identity = Identity::generate();
+    let server = Server::start(&identity).await;
+    server.add_keyboard();
+
+    let mut client = Client::start(&identity, server.port);
+    client.next().await;
+
+    drop(server);
+    client.error().await;
+}